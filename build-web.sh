@@ -39,9 +39,26 @@ fi
 echo -e "${GREEN}✓${NC} Emscripten SDK ready ($(emcc --version | head -1))"
 echo ""
 
+# Optional cargo features on top of the defaults, e.g. FEATURES="base64,cache".
+# For a build without QPDF: NO_DEFAULT_FEATURES=1 FEATURES=pdfium ./build-web.sh
+FEATURES="${FEATURES:-}"
+NO_DEFAULT_FEATURES="${NO_DEFAULT_FEATURES:-}"
+
+has_feature() {
+    [[ ",${FEATURES}," == *",$1,"* ]]
+}
+
+CARGO_FLAGS=()
+if [ -n "$NO_DEFAULT_FEATURES" ]; then
+    CARGO_FLAGS+=(--no-default-features)
+fi
+if [ -n "$FEATURES" ]; then
+    CARGO_FLAGS+=(--features "$FEATURES")
+fi
+
 # Step 1: Build Rust → WASM
 echo -e "${BLUE}[1/4]${NC} Building Rust to WASM..."
-cargo build --target wasm32-unknown-emscripten --release --quiet
+cargo build --target wasm32-unknown-emscripten --release --quiet "${CARGO_FLAGS[@]}"
 
 if [ $? -eq 0 ]; then
     echo -e "${GREEN}✓${NC} Rust compilation complete"
@@ -50,6 +67,15 @@ else
     exit 1
 fi
 
+# Exports of optional features are only listed when the feature is built
+EXPORTS="_pdfium_wasm_initialize,_pdfium_wasm_is_initialized,_pdfium_wasm_warm_up,_pdfium_wasm_extract_text,_pdfium_wasm_free_string,_pdfium_wasm_cleanup,_pdfium_wasm_load_custom_document,_pdfium_wasm_close_document,_pdfium_wasm_save_as_copy_custom,_pdfium_wasm_build_info_json,_FPDF_InitLibraryWithConfig,_FPDF_LoadMemDocument,_FPDF_GetPageCount,_FPDF_LoadPage,_FPDF_ClosePage,_FPDF_CloseDocument,_FPDFText_LoadPage,_FPDFText_ClosePage,_FPDFText_CountChars,_FPDFText_GetText,_IPDF_StreamingIO_LoadDocument,_IPDF_StreamingIO_SaveWithCallback,_IPDF_StreamingIO_GetPageCount,_IPDF_StreamingIO_GetPageSize,_IPDF_StreamingIO_GetPageText,_IPDF_StreamingIO_RenderPage,_IPDF_StreamingIO_FreeString"
+if [ -z "$NO_DEFAULT_FEATURES" ] || has_feature qpdf || has_feature json-typed; then
+    EXPORTS="${EXPORTS},_pdfium_wasm_pdf_to_json,_IPDF_QPDF_PDFToJSON,_IPDF_QPDF_FreeString,_IPDF_QPDF_StreamingOpen,_IPDF_QPDF_StreamingClose,_IPDF_QPDF_StreamingSave,_IPDF_QPDF_StreamingToJSON,_IPDF_QPDF_StreamingGetPageCount,_IPDF_QPDF_StreamingGetPDFVersion,_IPDF_QPDF_StreamingIsEncrypted,_IPDF_QPDF_StreamingIsLinearized,_IPDF_QPDF_StreamingGetLastError,_IPDF_QPDF_StreamingFreeString,_IPDF_QPDF_StreamingFreeBuffer"
+fi
+if has_feature base64; then
+    EXPORTS="${EXPORTS},_pdfium_wasm_extract_text_base64"
+fi
+
 # Step 2: Generate Emscripten JS glue
 echo -e "${BLUE}[2/4]${NC} Generating JavaScript glue code..."
 emcc target/wasm32-unknown-emscripten/release/libauto_pqdfium_rs.a \
@@ -57,7 +83,7 @@ emcc target/wasm32-unknown-emscripten/release/libauto_pqdfium_rs.a \
     -sERROR_ON_UNDEFINED_SYMBOLS=0 \
     -sALLOW_MEMORY_GROWTH=1 \
     -sALLOW_TABLE_GROWTH=1 \
-    -sEXPORTED_FUNCTIONS=_pdfium_wasm_initialize,_pdfium_wasm_extract_text,_pdfium_wasm_pdf_to_json,_pdfium_wasm_free_string,_pdfium_wasm_cleanup,_pdfium_wasm_load_custom_document,_pdfium_wasm_save_as_copy_custom,_FPDF_InitLibraryWithConfig,_FPDF_LoadMemDocument,_FPDF_GetPageCount,_FPDF_LoadPage,_FPDF_ClosePage,_FPDF_CloseDocument,_FPDFText_LoadPage,_FPDFText_ClosePage,_FPDFText_CountChars,_FPDFText_GetText,_IPDF_StreamingIO_LoadDocument,_IPDF_StreamingIO_SaveWithCallback,_IPDF_StreamingIO_GetPageCount,_IPDF_StreamingIO_GetPageSize,_IPDF_StreamingIO_GetPageText,_IPDF_StreamingIO_RenderPage,_IPDF_StreamingIO_FreeString,_IPDF_QPDF_PDFToJSON,_IPDF_QPDF_FreeString,_IPDF_QPDF_StreamingOpen,_IPDF_QPDF_StreamingClose,_IPDF_QPDF_StreamingSave,_IPDF_QPDF_StreamingToJSON,_IPDF_QPDF_StreamingGetPageCount,_IPDF_QPDF_StreamingGetPDFVersion,_IPDF_QPDF_StreamingIsEncrypted,_IPDF_QPDF_StreamingIsLinearized,_IPDF_QPDF_StreamingGetLastError,_IPDF_QPDF_StreamingFreeString,_IPDF_QPDF_StreamingFreeBuffer,_malloc,_free \
+    -sEXPORTED_FUNCTIONS="${EXPORTS},_malloc,_free" \
     -sEXPORTED_RUNTIME_METHODS=ccall,cwrap,UTF8ToString,stringToUTF8,lengthBytesUTF8,getValue,setValue,writeArrayToMemory,addFunction,removeFunction,HEAP8,HEAPU8,HEAP16,HEAPU16,HEAP32,HEAPU32,HEAPF32,HEAPF64 \
     -sINITIAL_MEMORY=1048576 \
     -sMODULARIZE=1 \
@@ -11,6 +11,9 @@ fn main() {
 
     println!("cargo:warning=Building for WebAssembly (Emscripten)");
 
+    // Exposed through build_info()
+    println!("cargo:rustc-env=PDFIUM_WASM_TARGET={}", target);
+
     // Use local assets directory for libraries
     let assets_dir = std::env::current_dir()
         .unwrap()
@@ -19,7 +22,11 @@ fn main() {
     // Link PDFium and QPDF static libraries from assets
     println!("cargo:rustc-link-search=native={}", assets_dir.display());
     println!("cargo:rustc-link-lib=static=pdfium");
-    println!("cargo:rustc-link-lib=static=qpdf");
+
+    // QPDF is optional; leaving it out shrinks the WASM binary considerably
+    if std::env::var_os("CARGO_FEATURE_QPDF").is_some() {
+        println!("cargo:rustc-link-lib=static=qpdf");
+    }
 
     // Compile C++ stub for C++ runtime support
     cc::Build::new()
@@ -3,7 +3,7 @@
 use thiserror::Error;
 
 /// Error types for PDFium operations
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum PdfiumError {
     #[error("Failed to initialize PDFium library")]
     InitializationFailed,
@@ -11,11 +11,47 @@ pub enum PdfiumError {
     #[error("Invalid PDF data")]
     InvalidData,
 
+    #[error("Document is encrypted and the password is missing or incorrect")]
+    PasswordRequired,
+
     #[error("Text extraction failed: {0}")]
     ExtractionFailed(String),
 
     #[error("PDF to JSON conversion failed: {0}")]
     ConversionFailed(String),
+
+    #[error("Page index {index} is out of range (document has {page_count} pages)")]
+    PageOutOfRange { index: usize, page_count: usize },
+
+    #[error("Page rendering failed: {0}")]
+    RenderFailed(String),
+
+    #[error("Saving PDF failed: {0}")]
+    SaveFailed(String),
+
+    #[error("Cannot clean up PDFium: {0} document(s) still open")]
+    ResourcesStillOpen(usize),
+
+    #[error("Malformed JSON: {0}")]
+    MalformedJson(String),
+
+    #[error("Document has no pages")]
+    NoPages,
+
+    #[error("Streaming reader exceeded its read limits")]
+    StreamLimitExceeded,
+
+    #[error("Document is {size} bytes, over the {limit}-byte limit")]
+    DocumentTooLarge { size: usize, limit: usize },
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    #[error("Not supported by this build: {0}")]
+    Unsupported(&'static str),
 }
 
 /// Convenient Result type for PDFium operations
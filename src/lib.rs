@@ -1,9 +1,11 @@
-use std::sync::Once;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
 mod error;
 pub use error::{PdfiumError, Result};
 
 mod ffi {
-    use std::os::raw::{c_char, c_int, c_uint, c_ulong, c_uchar, c_void};
+    use std::os::raw::{c_char, c_int, c_long, c_uint, c_ulong, c_uchar, c_void};
 
     // Opaque PDFium types
     #[allow(non_camel_case_types)]
@@ -12,11 +14,177 @@ mod ffi {
     pub type FPDF_PAGE = *mut c_void;
     #[allow(non_camel_case_types)]
     pub type FPDF_TEXTPAGE = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_SCHHANDLE = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_PAGEOBJECT = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_BITMAP = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_BOOKMARK = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_DEST = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_ACTION = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_LINK = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_FONT = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_ANNOTATION = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_FORMHANDLE = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_SIGNATURE = *mut c_void;
+    #[allow(non_camel_case_types)]
+    pub type FPDF_ATTACHMENT = *mut c_void;
 
     // Opaque QPDF streaming handle
+    #[cfg(feature = "qpdf")]
     #[allow(non_camel_case_types)]
     pub type QPDF_STREAM_HANDLE = *mut c_void;
 
+    // QPDF C API types (from qpdf-c.h)
+    #[cfg(feature = "qpdf")]
+    #[allow(non_camel_case_types)]
+    pub type qpdf_data = *mut c_void;
+    #[cfg(feature = "qpdf")]
+    #[allow(non_camel_case_types)]
+    pub type qpdf_error = *mut c_void;
+    #[cfg(feature = "qpdf")]
+    #[allow(non_camel_case_types)]
+    pub type QPDF_ERROR_CODE = c_int;
+    #[cfg(feature = "qpdf")]
+    #[allow(non_camel_case_types)]
+    pub type QPDF_BOOL = c_int;
+    #[cfg(feature = "qpdf")]
+    #[allow(non_camel_case_types)]
+    pub type qpdf_oh = c_uint;
+
+    // QPDF_ERROR_CODE bits
+    #[cfg(feature = "qpdf")]
+    pub const QPDF_ERRORS: QPDF_ERROR_CODE = 1 << 1;
+
+    // qpdf_error_code_e values
+    #[cfg(feature = "qpdf")]
+    pub const QPDF_E_PASSWORD: c_int = 4;
+
+    // qpdf_r3_print_e values
+    #[cfg(feature = "qpdf")]
+    pub const QPDF_R3P_FULL: c_int = 0;
+
+    // qpdf_object_stream_e values
+    #[cfg(feature = "qpdf")]
+    pub const QPDF_O_GENERATE: c_int = 2;
+
+    // qpdf_stream_decode_level_e values
+    #[cfg(feature = "qpdf")]
+    pub const QPDF_DL_GENERALIZED: c_int = 1;
+
+    // FPDFText_FindStart flags
+    pub const FPDF_MATCHCASE: c_ulong = 0x0000_0001;
+    pub const FPDF_MATCHWHOLEWORD: c_ulong = 0x0000_0002;
+
+    // FPDFTextObj_GetTextRenderMode values
+    pub const FPDF_TEXTRENDERMODE_INVISIBLE: c_int = 3;
+
+    // FPDFPageObj_GetType values
+    pub const FPDF_PAGEOBJ_TEXT: c_int = 1;
+    pub const FPDF_PAGEOBJ_IMAGE: c_int = 3;
+    pub const FPDF_PAGEOBJ_FORM: c_int = 5;
+
+    // FPDFBitmap_GetFormat values
+    pub const FPDFBITMAP_GRAY: c_int = 1;
+    pub const FPDFBITMAP_BGR: c_int = 2;
+    pub const FPDFBITMAP_BGRX: c_int = 3;
+    pub const FPDFBITMAP_BGRA: c_int = 4;
+
+    // FPDF_GetLastError values
+    pub const FPDF_ERR_PASSWORD: c_ulong = 4;
+
+    // FPDF_GetDocPermissions bits (PDF 32000-1, table 22)
+    pub const FPDF_PERM_PRINT: c_ulong = 1 << 2;
+    pub const FPDF_PERM_MODIFY: c_ulong = 1 << 3;
+    pub const FPDF_PERM_COPY: c_ulong = 1 << 4;
+
+    // FPDFAction_GetType values
+    pub const PDFACTION_GOTO: c_ulong = 1;
+    pub const PDFACTION_URI: c_ulong = 3;
+
+    // FPDFAnnot_GetSubtype values without user-authored text
+    pub const FPDF_ANNOT_LINK: c_int = 2;
+    pub const FPDF_ANNOT_POPUP: c_int = 16;
+    pub const FPDF_ANNOT_WIDGET: c_int = 20;
+
+    // FPDFAnnot_GetFormFieldType values
+    pub const FPDF_FORMFIELD_PUSHBUTTON: c_int = 1;
+    pub const FPDF_FORMFIELD_CHECKBOX: c_int = 2;
+    pub const FPDF_FORMFIELD_RADIOBUTTON: c_int = 3;
+    pub const FPDF_FORMFIELD_COMBOBOX: c_int = 4;
+    pub const FPDF_FORMFIELD_LISTBOX: c_int = 5;
+    pub const FPDF_FORMFIELD_TEXTFIELD: c_int = 6;
+    pub const FPDF_FORMFIELD_SIGNATURE: c_int = 7;
+
+    // FPDFPath_SetDrawMode fill modes
+    pub const FPDF_FILLMODE_WINDING: c_int = 2;
+
+    // FPDFPage_Flatten flags and results
+    pub const FLAT_NORMALDISPLAY: c_int = 0;
+    pub const FLATTEN_FAIL: c_int = 0;
+
+    // FPDF_RenderPageBitmap flags
+    pub const FPDF_ANNOT: c_int = 0x01;
+    pub const FPDF_LCD_TEXT: c_int = 0x02;
+    pub const FPDF_GRAYSCALE: c_int = 0x08;
+
+    // FPDF_SaveAsCopy flags
+    pub const FPDF_INCREMENTAL: c_int = 1;
+
+    // Rectangle in page coordinates (fpdfview.h)
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct FS_RECTF {
+        pub left: f32,
+        pub top: f32,
+        pub right: f32,
+        pub bottom: f32,
+    }
+
+    // 2D affine transform [a b c d e f] (fpdfview.h)
+    #[repr(C)]
+    pub struct FS_MATRIX {
+        pub a: f32,
+        pub b: f32,
+        pub c: f32,
+        pub d: f32,
+        pub e: f32,
+        pub f: f32,
+    }
+
+    // Image object properties (fpdf_edit.h)
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct FPDF_IMAGEOBJ_METADATA {
+        pub width: c_uint,
+        pub height: c_uint,
+        pub horizontal_dpi: f32,
+        pub vertical_dpi: f32,
+        pub bits_per_pixel: c_uint,
+        pub colorspace: c_int,
+        pub marked_content_id: c_int,
+    }
+
+    // Form-fill environment description (fpdf_formfill.h), version 1 layout.
+    // PDFium checks each callback for null before calling it.
+    #[repr(C)]
+    #[allow(non_snake_case)]
+    pub struct FPDF_FORMFILLINFO {
+        pub version: c_int,
+        // Release through FFI_DoGoToAction
+        pub callbacks: [*mut c_void; 15],
+        pub m_pJsPlatform: *mut c_void,
+    }
+
     // PDFium config structure
     #[repr(C)]
     #[allow(non_snake_case)]
@@ -37,9 +205,113 @@ mod ffi {
             password: *const c_char,
         ) -> FPDF_DOCUMENT;
         pub fn FPDF_CloseDocument(document: FPDF_DOCUMENT);
+        pub fn FPDF_CreateNewDocument() -> FPDF_DOCUMENT;
+        pub fn FPDF_ImportPages(
+            dest_doc: FPDF_DOCUMENT,
+            src_doc: FPDF_DOCUMENT,
+            pagerange: *const c_char,
+            index: c_int,
+        ) -> c_int;
+        pub fn FPDF_ImportPagesByIndex(
+            dest_doc: FPDF_DOCUMENT,
+            src_doc: FPDF_DOCUMENT,
+            page_indices: *const c_int,
+            length: c_ulong,
+            index: c_int,
+        ) -> c_int;
+        pub fn FPDF_GetLastError() -> c_ulong;
+        pub fn FPDF_GetDocPermissions(document: FPDF_DOCUMENT) -> c_ulong;
+        pub fn FPDF_GetSecurityHandlerRevision(document: FPDF_DOCUMENT) -> c_int;
+        pub fn FPDF_GetFileVersion(doc: FPDF_DOCUMENT, file_version: *mut c_int) -> c_int;
+        pub fn FPDFDoc_GetAttachmentCount(document: FPDF_DOCUMENT) -> c_int;
+        pub fn FPDFDoc_GetAttachment(document: FPDF_DOCUMENT, index: c_int) -> FPDF_ATTACHMENT;
+        pub fn FPDFAttachment_GetName(
+            attachment: FPDF_ATTACHMENT,
+            buffer: *mut c_void,
+            buflen: c_ulong,
+        ) -> c_ulong;
+        pub fn FPDFAttachment_GetFile(
+            attachment: FPDF_ATTACHMENT,
+            buffer: *mut c_void,
+            buflen: c_ulong,
+            out_buflen: *mut c_ulong,
+        ) -> c_int;
+        pub fn FPDF_GetSignatureCount(document: FPDF_DOCUMENT) -> c_int;
+        pub fn FPDF_GetSignatureObject(document: FPDF_DOCUMENT, index: c_int) -> FPDF_SIGNATURE;
+        pub fn FPDFSignatureObj_GetContents(
+            signature: FPDF_SIGNATURE,
+            buffer: *mut c_void,
+            length: c_ulong,
+        ) -> c_ulong;
+        pub fn FPDFSignatureObj_GetByteRange(
+            signature: FPDF_SIGNATURE,
+            buffer: *mut c_int,
+            length: c_ulong,
+        ) -> c_ulong;
+        pub fn FPDFSignatureObj_GetReason(
+            signature: FPDF_SIGNATURE,
+            buffer: *mut c_void,
+            length: c_ulong,
+        ) -> c_ulong;
+        pub fn FPDFSignatureObj_GetTime(
+            signature: FPDF_SIGNATURE,
+            buffer: *mut c_char,
+            length: c_ulong,
+        ) -> c_ulong;
+        // Only needed alongside QPDF's XMP access (conformance_info)
+        #[cfg(feature = "qpdf")]
+        pub fn FPDFCatalog_IsTagged(document: FPDF_DOCUMENT) -> c_int;
         pub fn FPDF_GetPageCount(document: FPDF_DOCUMENT) -> c_int;
         pub fn FPDF_LoadPage(document: FPDF_DOCUMENT, page_index: c_int) -> FPDF_PAGE;
         pub fn FPDF_ClosePage(page: FPDF_PAGE);
+        pub fn FPDFPage_GetRotation(page: FPDF_PAGE) -> c_int;
+        pub fn FPDF_GetPageBoundingBox(page: FPDF_PAGE, rect: *mut FS_RECTF) -> c_int;
+        pub fn FPDFPage_GetMediaBox(
+            page: FPDF_PAGE,
+            left: *mut f32,
+            bottom: *mut f32,
+            right: *mut f32,
+            top: *mut f32,
+        ) -> c_int;
+        pub fn FPDFPage_GetCropBox(
+            page: FPDF_PAGE,
+            left: *mut f32,
+            bottom: *mut f32,
+            right: *mut f32,
+            top: *mut f32,
+        ) -> c_int;
+        pub fn FPDFPage_SetRotation(page: FPDF_PAGE, rotate: c_int);
+        pub fn FPDFPage_Delete(document: FPDF_DOCUMENT, page_index: c_int);
+        pub fn FPDFPage_Flatten(page: FPDF_PAGE, flag: c_int) -> c_int;
+        pub fn FPDFPage_GetAnnotCount(page: FPDF_PAGE) -> c_int;
+        pub fn FPDFPage_GetAnnot(page: FPDF_PAGE, index: c_int) -> FPDF_ANNOTATION;
+        pub fn FPDFPage_CloseAnnot(annot: FPDF_ANNOTATION);
+        pub fn FPDFAnnot_GetSubtype(annot: FPDF_ANNOTATION) -> c_int;
+        pub fn FPDFAnnot_GetRect(annot: FPDF_ANNOTATION, rect: *mut FS_RECTF) -> c_int;
+        pub fn FPDFAnnot_GetStringValue(
+            annot: FPDF_ANNOTATION,
+            key: *const c_char,
+            buffer: *mut u16,
+            buflen: c_ulong,
+        ) -> c_ulong;
+        pub fn FPDFDOC_InitFormFillEnvironment(
+            document: FPDF_DOCUMENT,
+            form_info: *mut FPDF_FORMFILLINFO,
+        ) -> FPDF_FORMHANDLE;
+        pub fn FPDFDOC_ExitFormFillEnvironment(handle: FPDF_FORMHANDLE);
+        pub fn FPDFAnnot_GetFormFieldType(handle: FPDF_FORMHANDLE, annot: FPDF_ANNOTATION) -> c_int;
+        pub fn FPDFAnnot_GetFormFieldName(
+            handle: FPDF_FORMHANDLE,
+            annot: FPDF_ANNOTATION,
+            buffer: *mut u16,
+            buflen: c_ulong,
+        ) -> c_ulong;
+        pub fn FPDFAnnot_GetFormFieldValue(
+            handle: FPDF_FORMHANDLE,
+            annot: FPDF_ANNOTATION,
+            buffer: *mut u16,
+            buflen: c_ulong,
+        ) -> c_ulong;
         pub fn FPDFText_LoadPage(page: FPDF_PAGE) -> FPDF_TEXTPAGE;
         pub fn FPDFText_ClosePage(text_page: FPDF_TEXTPAGE);
         pub fn FPDFText_CountChars(text_page: FPDF_TEXTPAGE) -> c_int;
@@ -49,13 +321,163 @@ mod ffi {
             count: c_int,
             result: *mut u16,
         ) -> c_int;
-        pub fn IPDF_QPDF_PDFToJSON(
-            pdf_data: *const c_void,
-            pdf_size: usize,
-            version: c_int,
-        ) -> *mut c_char;
-        pub fn IPDF_QPDF_FreeString(str: *mut c_char);
-
+        pub fn FPDFText_GetUnicode(text_page: FPDF_TEXTPAGE, index: c_int) -> c_uint;
+        pub fn FPDFText_GetFontSize(text_page: FPDF_TEXTPAGE, index: c_int) -> f64;
+        pub fn FPDFText_GetTextObject(text_page: FPDF_TEXTPAGE, index: c_int) -> FPDF_PAGEOBJECT;
+        pub fn FPDFText_IsGenerated(text_page: FPDF_TEXTPAGE, index: c_int) -> c_int;
+        pub fn FPDFTextObj_GetTextRenderMode(text: FPDF_PAGEOBJECT) -> c_int;
+        pub fn FPDFText_GetBoundedText(
+            text_page: FPDF_TEXTPAGE,
+            left: f64,
+            top: f64,
+            right: f64,
+            bottom: f64,
+            buffer: *mut u16,
+            buflen: c_int,
+        ) -> c_int;
+        pub fn FPDFText_GetCharBox(
+            text_page: FPDF_TEXTPAGE,
+            index: c_int,
+            left: *mut f64,
+            right: *mut f64,
+            bottom: *mut f64,
+            top: *mut f64,
+        ) -> c_int;
+        pub fn FPDFText_GetFontInfo(
+            text_page: FPDF_TEXTPAGE,
+            index: c_int,
+            buffer: *mut c_void,
+            buflen: c_ulong,
+            flags: *mut c_int,
+        ) -> c_ulong;
+        pub fn FPDF_GetMetaText(
+            document: FPDF_DOCUMENT,
+            tag: *const c_char,
+            buffer: *mut c_void,
+            buflen: c_ulong,
+        ) -> c_ulong;
+        pub fn FPDF_GetPageLabel(
+            document: FPDF_DOCUMENT,
+            page_index: c_int,
+            buffer: *mut c_void,
+            buflen: c_ulong,
+        ) -> c_ulong;
+        pub fn FPDFText_FindStart(
+            text_page: FPDF_TEXTPAGE,
+            findwhat: *const u16,
+            flags: c_ulong,
+            start_index: c_int,
+        ) -> FPDF_SCHHANDLE;
+        pub fn FPDFText_FindNext(handle: FPDF_SCHHANDLE) -> c_int;
+        pub fn FPDFText_GetSchResultIndex(handle: FPDF_SCHHANDLE) -> c_int;
+        pub fn FPDFText_GetSchCount(handle: FPDF_SCHHANDLE) -> c_int;
+        pub fn FPDFText_FindClose(handle: FPDF_SCHHANDLE);
+        pub fn FPDFPage_CountObjects(page: FPDF_PAGE) -> c_int;
+        pub fn FPDFPage_GetObject(page: FPDF_PAGE, index: c_int) -> FPDF_PAGEOBJECT;
+        pub fn FPDFPageObj_GetType(page_object: FPDF_PAGEOBJECT) -> c_int;
+        pub fn FPDFPageObj_GetBounds(
+            page_object: FPDF_PAGEOBJECT,
+            left: *mut f32,
+            bottom: *mut f32,
+            right: *mut f32,
+            top: *mut f32,
+        ) -> c_int;
+        pub fn FPDFPageObj_Destroy(page_object: FPDF_PAGEOBJECT);
+        pub fn FPDFPageObj_CreateNewRect(x: f32, y: f32, w: f32, h: f32) -> FPDF_PAGEOBJECT;
+        pub fn FPDFPageObj_SetFillColor(
+            page_object: FPDF_PAGEOBJECT,
+            r: c_uint,
+            g: c_uint,
+            b: c_uint,
+            a: c_uint,
+        ) -> c_int;
+        pub fn FPDFPath_SetDrawMode(path: FPDF_PAGEOBJECT, fillmode: c_int, stroke: c_int) -> c_int;
+        pub fn FPDFPage_InsertObject(page: FPDF_PAGE, page_object: FPDF_PAGEOBJECT);
+        pub fn FPDFPage_RemoveObject(page: FPDF_PAGE, page_object: FPDF_PAGEOBJECT) -> c_int;
+        pub fn FPDFPage_GenerateContent(page: FPDF_PAGE) -> c_int;
+        pub fn FPDFFormObj_CountObjects(form_object: FPDF_PAGEOBJECT) -> c_int;
+        pub fn FPDFFormObj_GetObject(form_object: FPDF_PAGEOBJECT, index: c_ulong) -> FPDF_PAGEOBJECT;
+        pub fn FPDFTextObj_GetFont(text: FPDF_PAGEOBJECT) -> FPDF_FONT;
+        pub fn FPDFFont_GetBaseFontName(font: FPDF_FONT, buffer: *mut c_char, length: usize) -> usize;
+        pub fn FPDFFont_GetIsEmbedded(font: FPDF_FONT) -> c_int;
+        pub fn FPDFImageObj_GetBitmap(image_object: FPDF_PAGEOBJECT) -> FPDF_BITMAP;
+        pub fn FPDFImageObj_GetImageMetadata(
+            image_object: FPDF_PAGEOBJECT,
+            page: FPDF_PAGE,
+            metadata: *mut FPDF_IMAGEOBJ_METADATA,
+        ) -> c_int;
+        pub fn FPDFBitmap_GetFormat(bitmap: FPDF_BITMAP) -> c_int;
+        pub fn FPDFBitmap_GetWidth(bitmap: FPDF_BITMAP) -> c_int;
+        pub fn FPDFBitmap_GetHeight(bitmap: FPDF_BITMAP) -> c_int;
+        pub fn FPDFBitmap_GetStride(bitmap: FPDF_BITMAP) -> c_int;
+        pub fn FPDFBitmap_GetBuffer(bitmap: FPDF_BITMAP) -> *mut c_void;
+        pub fn FPDFBitmap_Destroy(bitmap: FPDF_BITMAP);
+        pub fn FPDFBitmap_Create(width: c_int, height: c_int, alpha: c_int) -> FPDF_BITMAP;
+        pub fn FPDFPage_GetThumbnailAsBitmap(page: FPDF_PAGE) -> FPDF_BITMAP;
+        pub fn FPDFBitmap_FillRect(
+            bitmap: FPDF_BITMAP,
+            left: c_int,
+            top: c_int,
+            width: c_int,
+            height: c_int,
+            color: c_ulong,
+        ) -> c_int;
+        pub fn FPDF_RenderPageBitmap(
+            bitmap: FPDF_BITMAP,
+            page: FPDF_PAGE,
+            start_x: c_int,
+            start_y: c_int,
+            size_x: c_int,
+            size_y: c_int,
+            rotate: c_int,
+            flags: c_int,
+        );
+        pub fn FPDF_RenderPageBitmapWithMatrix(
+            bitmap: FPDF_BITMAP,
+            page: FPDF_PAGE,
+            matrix: *const FS_MATRIX,
+            clipping: *const FS_RECTF,
+            flags: c_int,
+        );
+        pub fn FPDFBookmark_GetFirstChild(
+            document: FPDF_DOCUMENT,
+            bookmark: FPDF_BOOKMARK,
+        ) -> FPDF_BOOKMARK;
+        pub fn FPDFBookmark_GetNextSibling(
+            document: FPDF_DOCUMENT,
+            bookmark: FPDF_BOOKMARK,
+        ) -> FPDF_BOOKMARK;
+        pub fn FPDFBookmark_GetTitle(
+            bookmark: FPDF_BOOKMARK,
+            buffer: *mut c_void,
+            buflen: c_ulong,
+        ) -> c_ulong;
+        pub fn FPDFBookmark_GetDest(document: FPDF_DOCUMENT, bookmark: FPDF_BOOKMARK) -> FPDF_DEST;
+        pub fn FPDFBookmark_GetAction(bookmark: FPDF_BOOKMARK) -> FPDF_ACTION;
+        pub fn FPDFAction_GetDest(document: FPDF_DOCUMENT, action: FPDF_ACTION) -> FPDF_DEST;
+        pub fn FPDFDest_GetDestPageIndex(document: FPDF_DOCUMENT, dest: FPDF_DEST) -> c_int;
+        pub fn FPDF_CountNamedDests(document: FPDF_DOCUMENT) -> c_ulong;
+        pub fn FPDF_GetNamedDest(
+            document: FPDF_DOCUMENT,
+            index: c_int,
+            buffer: *mut c_void,
+            buflen: *mut c_long,
+        ) -> FPDF_DEST;
+        pub fn FPDFAction_GetType(action: FPDF_ACTION) -> c_ulong;
+        pub fn FPDFAction_GetURIPath(
+            document: FPDF_DOCUMENT,
+            action: FPDF_ACTION,
+            buffer: *mut c_void,
+            buflen: c_ulong,
+        ) -> c_ulong;
+        pub fn FPDFLink_Enumerate(
+            page: FPDF_PAGE,
+            start_pos: *mut c_int,
+            link_annot: *mut FPDF_LINK,
+        ) -> c_int;
+        pub fn FPDFLink_GetAnnotRect(link_annot: FPDF_LINK, rect: *mut FS_RECTF) -> c_int;
+        pub fn FPDFLink_GetDest(document: FPDF_DOCUMENT, link: FPDF_LINK) -> FPDF_DEST;
+        pub fn FPDFLink_GetAction(link: FPDF_LINK) -> FPDF_ACTION;
         // Streaming I/O functions (directly from Universal.Pdfium)
         pub fn IPDF_StreamingIO_LoadDocument(
             file_size: c_ulong,
@@ -94,6 +516,17 @@ mod ffi {
             out_size: *mut c_ulong,
         ) -> *mut c_uchar;
         pub fn IPDF_StreamingIO_FreeString(ptr: *mut c_void);
+    }
+
+    // Everything below needs libqpdf.a, which is only linked with the `qpdf` feature
+    #[cfg(feature = "qpdf")]
+    extern "C" {
+        pub fn IPDF_QPDF_PDFToJSON(
+            pdf_data: *const c_void,
+            pdf_size: usize,
+            version: c_int,
+        ) -> *mut c_char;
+        pub fn IPDF_QPDF_FreeString(str: *mut c_char);
 
         // ============================================================================
         // QPDF Streaming I/O API (from ipdf_qpdf_streaming.h)
@@ -148,6 +581,112 @@ mod ffi {
         // Memory Management
         pub fn IPDF_QPDF_StreamingFreeString(str: *mut c_char);
         pub fn IPDF_QPDF_StreamingFreeBuffer(buffer: *mut c_void);
+
+        // ============================================================================
+        // QPDF C API (from qpdf-c.h, linked from libqpdf.a)
+        // ============================================================================
+
+        pub fn qpdf_init() -> qpdf_data;
+        pub fn qpdf_cleanup(qpdf: *mut qpdf_data);
+        pub fn qpdf_get_error(qpdf: qpdf_data) -> qpdf_error;
+        pub fn qpdf_get_error_code(qpdf: qpdf_data, error: qpdf_error) -> c_int;
+        pub fn qpdf_get_error_full_text(qpdf: qpdf_data, error: qpdf_error) -> *const c_char;
+        pub fn qpdf_more_warnings(qpdf: qpdf_data) -> QPDF_BOOL;
+        pub fn qpdf_next_warning(qpdf: qpdf_data) -> qpdf_error;
+        pub fn qpdf_check_pdf(qpdf: qpdf_data) -> QPDF_ERROR_CODE;
+        pub fn qpdf_set_suppress_warnings(qpdf: qpdf_data, value: QPDF_BOOL);
+        pub fn qpdf_read_memory(
+            qpdf: qpdf_data,
+            description: *const c_char,
+            buffer: *const c_char,
+            size: u64,
+            password: *const c_char,
+        ) -> QPDF_ERROR_CODE;
+        pub fn qpdf_init_write_memory(qpdf: qpdf_data) -> QPDF_ERROR_CODE;
+        pub fn qpdf_set_linearization(qpdf: qpdf_data, value: QPDF_BOOL);
+        pub fn qpdf_set_object_stream_mode(qpdf: qpdf_data, mode: c_int);
+        pub fn qpdf_set_compress_streams(qpdf: qpdf_data, value: QPDF_BOOL);
+        pub fn qpdf_set_preserve_encryption(qpdf: qpdf_data, value: QPDF_BOOL);
+        pub fn qpdf_set_r2_encryption_parameters_insecure(
+            qpdf: qpdf_data,
+            user_password: *const c_char,
+            owner_password: *const c_char,
+            allow_print: QPDF_BOOL,
+            allow_modify: QPDF_BOOL,
+            allow_extract: QPDF_BOOL,
+            allow_annotate: QPDF_BOOL,
+        );
+        pub fn qpdf_set_r4_encryption_parameters_insecure(
+            qpdf: qpdf_data,
+            user_password: *const c_char,
+            owner_password: *const c_char,
+            allow_accessibility: QPDF_BOOL,
+            allow_extract: QPDF_BOOL,
+            allow_assemble: QPDF_BOOL,
+            allow_annotate_and_form: QPDF_BOOL,
+            allow_form_filling: QPDF_BOOL,
+            allow_modify_other: QPDF_BOOL,
+            print: c_int,
+            encrypt_metadata: QPDF_BOOL,
+            use_aes: QPDF_BOOL,
+        );
+        pub fn qpdf_set_r6_encryption_parameters2(
+            qpdf: qpdf_data,
+            user_password: *const c_char,
+            owner_password: *const c_char,
+            allow_accessibility: QPDF_BOOL,
+            allow_extract: QPDF_BOOL,
+            allow_assemble: QPDF_BOOL,
+            allow_annotate_and_form: QPDF_BOOL,
+            allow_form_filling: QPDF_BOOL,
+            allow_modify_other: QPDF_BOOL,
+            print: c_int,
+            encrypt_metadata: QPDF_BOOL,
+        );
+        pub fn qpdf_write(qpdf: qpdf_data) -> QPDF_ERROR_CODE;
+        pub fn qpdf_get_buffer_length(qpdf: qpdf_data) -> usize;
+        pub fn qpdf_get_buffer(qpdf: qpdf_data) -> *const c_uchar;
+        pub fn qpdf_get_root(qpdf: qpdf_data) -> qpdf_oh;
+        pub fn qpdf_get_trailer(qpdf: qpdf_data) -> qpdf_oh;
+        pub fn qpdf_make_indirect_object(qpdf: qpdf_data, oh: qpdf_oh) -> qpdf_oh;
+        pub fn qpdf_oh_new_dictionary(qpdf: qpdf_data) -> qpdf_oh;
+        pub fn qpdf_oh_new_unicode_string(qpdf: qpdf_data, utf8_str: *const c_char) -> qpdf_oh;
+        pub fn qpdf_oh_is_dictionary(qpdf: qpdf_data, oh: qpdf_oh) -> QPDF_BOOL;
+        pub fn qpdf_oh_is_array(qpdf: qpdf_data, oh: qpdf_oh) -> QPDF_BOOL;
+        pub fn qpdf_oh_is_name_and_equals(
+            qpdf: qpdf_data,
+            oh: qpdf_oh,
+            name: *const c_char,
+        ) -> QPDF_BOOL;
+        pub fn qpdf_oh_has_key(qpdf: qpdf_data, oh: qpdf_oh, key: *const c_char) -> QPDF_BOOL;
+        pub fn qpdf_oh_remove_key(qpdf: qpdf_data, oh: qpdf_oh, key: *const c_char);
+        pub fn qpdf_oh_begin_dict_key_iter(qpdf: qpdf_data, dict: qpdf_oh);
+        pub fn qpdf_oh_dict_more_keys(qpdf: qpdf_data) -> QPDF_BOOL;
+        pub fn qpdf_oh_dict_next_key(qpdf: qpdf_data) -> *const c_char;
+        pub fn qpdf_oh_get_array_n_items(qpdf: qpdf_data, oh: qpdf_oh) -> c_int;
+        pub fn qpdf_oh_get_array_item(qpdf: qpdf_data, oh: qpdf_oh, n: c_int) -> qpdf_oh;
+        pub fn qpdf_oh_erase_item(qpdf: qpdf_data, oh: qpdf_oh, at: c_int);
+        pub fn qpdf_get_num_pages(qpdf: qpdf_data) -> c_int;
+        pub fn qpdf_get_page_n(qpdf: qpdf_data, zero_based_index: usize) -> qpdf_oh;
+        pub fn qpdf_oh_replace_key(
+            qpdf: qpdf_data,
+            oh: qpdf_oh,
+            key: *const c_char,
+            item: qpdf_oh,
+        );
+        pub fn qpdf_oh_get_key(qpdf: qpdf_data, oh: qpdf_oh, key: *const c_char) -> qpdf_oh;
+        pub fn qpdf_oh_is_stream(qpdf: qpdf_data, oh: qpdf_oh) -> QPDF_BOOL;
+        pub fn qpdf_oh_get_stream_data(
+            qpdf: qpdf_data,
+            stream_oh: qpdf_oh,
+            decode_level: c_int,
+            filtered: *mut QPDF_BOOL,
+            bufp: *mut *mut c_uchar,
+            len: *mut usize,
+        ) -> QPDF_ERROR_CODE;
+
+        // C library allocator, for buffers QPDF hands over with malloc
+        pub fn free(ptr: *mut c_void);
     }
 
     // Type aliases for better readability
@@ -157,38 +696,212 @@ mod ffi {
         Option<unsafe extern "C" fn(*mut c_void, *const c_void, c_ulong) -> c_int>;
 
     // QPDF streaming callback types (uses c_int for WASM32 compatibility)
+    #[cfg(feature = "qpdf")]
     pub type QPDFReadBlockCallback =
         Option<unsafe extern "C" fn(*mut c_void, c_int, *mut c_uchar, c_int) -> c_int>;
+    #[cfg(feature = "qpdf")]
     pub type QPDFWriteBlockCallback =
         Option<unsafe extern "C" fn(*mut c_void, *const c_void, c_int) -> c_int>;
 }
 
-static INIT: Once = Once::new();
+// ============================================================================
+// Library Lifecycle
+// ============================================================================
+
+/// Whether `FPDF_InitLibraryWithConfig` has run since the last `cleanup`
+///
+/// The mutex also serializes `initialize`, `cleanup` and document loading, so a
+/// teardown can never race with a document being opened.
+static LIBRARY_INITIALIZED: Mutex<bool> = Mutex::new(false);
+
+/// Number of live `Document` handles
+static OPEN_DOCUMENTS: AtomicUsize = AtomicUsize::new(0);
+
+fn lock_library() -> Result<MutexGuard<'static, bool>> {
+    LIBRARY_INITIALIZED
+        .lock()
+        .map_err(|_| PdfiumError::InitializationFailed)
+}
+
+/// Run the PDFium configuration if it hasn't run yet (caller holds the library lock)
+fn init_locked(initialized: &mut bool) {
+    if *initialized {
+        return;
+    }
+
+    unsafe {
+        let config = ffi::FPDF_LIBRARY_CONFIG {
+            version: 2,
+            m_pUserFontPaths: std::ptr::null_mut(),
+            m_pIsolate: std::ptr::null_mut(),
+            m_v8EmbedderSlot: 0,
+        };
+        ffi::FPDF_InitLibraryWithConfig(&config);
+    }
+    *initialized = true;
+}
 
+/// Initialize PDFium library
+///
+/// Safe to call repeatedly; the configuration only runs once until `cleanup`
+/// tears the library down, after which the next call initializes it again.
 pub fn initialize() -> Result<()> {
-    INIT.call_once(|| {
-        unsafe {
-            let config = ffi::FPDF_LIBRARY_CONFIG {
-                version: 2,
-                m_pUserFontPaths: std::ptr::null_mut(),
-                m_pIsolate: std::ptr::null_mut(),
-                m_v8EmbedderSlot: 0,
-            };
-            ffi::FPDF_InitLibraryWithConfig(&config);
+    let mut initialized = lock_library()?;
+    init_locked(&mut initialized);
+    Ok(())
+}
+
+/// Whether PDFium is currently initialized
+///
+/// Briefly takes the library lock, so it waits for an `initialize`,
+/// `cleanup` or document load in progress on another thread.
+pub fn is_initialized() -> bool {
+    lock_library().is_ok_and(|initialized| *initialized)
+}
+
+/// Front-load PDFium's one-time startup cost
+///
+/// Initializes the library and creates and closes an empty document, which
+/// faults in the allocator and document machinery before the first real load.
+/// Meant to be called from an idle callback ahead of the first user action.
+/// The work is still synchronous: it blocks the calling thread while it runs,
+/// it just moves the delay to a moment of the host's choosing.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InitializationFailed` if the library lock is poisoned.
+/// Returns `PdfiumError::SaveFailed` if PDFium cannot create a document.
+pub fn warm_up() -> Result<()> {
+    initialize()?;
+    Document::new_empty().map(drop)
+}
+
+/// Full `FPDF_LIBRARY_CONFIG` settings for `initialize_with_config`
+#[derive(Debug, Clone)]
+pub struct InitConfig {
+    /// `v8::Isolate` for JavaScript-enabled builds, or null to let PDFium create one
+    pub isolate: *mut std::ffi::c_void,
+    /// Embedder data slot PDFium may use in the isolate
+    pub v8_slot: u32,
+    /// Extra directories to search for system fonts
+    pub font_paths: Vec<String>,
+}
+
+impl Default for InitConfig {
+    /// The configuration `initialize` uses: no isolate, slot 0, default font paths
+    fn default() -> Self {
+        InitConfig {
+            isolate: std::ptr::null_mut(),
+            v8_slot: 0,
+            font_paths: Vec::new(),
         }
-    });
+    }
+}
+
+/// Initialize PDFium library with an explicit configuration
+///
+/// The configuration can only take effect when the library is initialized, so
+/// this fails if `initialize` (or any function that loads a document) has
+/// already run; call `cleanup` first to reconfigure. The isolate and slot are
+/// ignored by PDFium builds without V8, such as the default WASM build.
+///
+/// # Arguments
+///
+/// * `config` - Isolate, embedder slot and font search paths
+///
+/// # Returns
+///
+/// Returns `Ok(())` once PDFium is initialized with `config`.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InitializationFailed` if the library is already
+/// initialized or its lock is poisoned.
+/// Returns `PdfiumError::InvalidData` if a font path contains a NUL byte.
+///
+/// # Safety
+///
+/// `config.isolate` must be null or point to a live `v8::Isolate` that
+/// outlives the library (until `cleanup`).
+pub unsafe fn initialize_with_config(config: InitConfig) -> Result<()> {
+    let font_paths = config
+        .font_paths
+        .iter()
+        .map(|path| std::ffi::CString::new(path.as_str()).map_err(|_| PdfiumError::InvalidData))
+        .collect::<Result<Vec<_>>>()?;
+
+    // NULL-terminated array; PDFium copies the paths during initialization
+    let mut font_path_ptrs: Vec<*const std::os::raw::c_char> =
+        font_paths.iter().map(|path| path.as_ptr()).collect();
+    font_path_ptrs.push(std::ptr::null());
+
+    let mut initialized = lock_library()?;
+    if *initialized {
+        return Err(PdfiumError::InitializationFailed);
+    }
+
+    let ffi_config = ffi::FPDF_LIBRARY_CONFIG {
+        version: 2,
+        m_pUserFontPaths: if font_paths.is_empty() {
+            std::ptr::null_mut()
+        } else {
+            font_path_ptrs.as_mut_ptr()
+        },
+        m_pIsolate: config.isolate,
+        m_v8EmbedderSlot: config.v8_slot,
+    };
+    ffi::FPDF_InitLibraryWithConfig(&ffi_config);
+    *initialized = true;
 
     Ok(())
 }
 
+/// Run the body of a C ABI export, returning `fallback` if it panics
+///
+/// Unwinding out of an `extern "C"` function is undefined behavior, so every
+/// `#[no_mangle]` export goes through this.
+fn ffi_guard<T>(fallback: T, body: impl FnOnce() -> T) -> T {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)).unwrap_or(fallback)
+}
+
+/// Hand a string to C as a NUL-terminated buffer (free with `pdfium_wasm_free_string`)
+///
+/// Interior NULs, which some PDFs really do contain, become U+FFFD instead of
+/// truncating the string or failing the conversion.
+fn into_c_string(text: String) -> *mut u8 {
+    let text = if text.contains('\0') {
+        text.replace('\0', "\u{FFFD}")
+    } else {
+        text
+    };
+    std::ffi::CString::new(text).unwrap_or_default().into_raw() as *mut u8
+}
+
 /// Initialize PDFium library (C ABI for WASM)
 /// Returns 1 on success, 0 on failure
 #[no_mangle]
 pub extern "C" fn pdfium_wasm_initialize() -> i32 {
-    match initialize() {
+    ffi_guard(0, || match initialize() {
         Ok(_) => 1,
         Err(_) => 0,
-    }
+    })
+}
+
+/// Check whether PDFium is initialized (C ABI for WASM)
+/// Returns 1 if initialized, 0 otherwise
+#[no_mangle]
+pub extern "C" fn pdfium_wasm_is_initialized() -> i32 {
+    ffi_guard(0, || is_initialized() as i32)
+}
+
+/// Front-load PDFium's startup cost, see `warm_up` (C ABI for WASM)
+/// Returns 1 on success, 0 on failure
+#[no_mangle]
+pub extern "C" fn pdfium_wasm_warm_up() -> i32 {
+    ffi_guard(0, || match warm_up() {
+        Ok(_) => 1,
+        Err(_) => 0,
+    })
 }
 
 /// Extract text from a PDF document
@@ -200,134 +913,771 @@ pub extern "C" fn pdfium_wasm_initialize() -> i32 {
 /// # Returns
 ///
 /// Returns the extracted text as a String. Pages are separated by "---PAGE BREAK---".
+/// A document with zero pages yields `Ok("")`; use `extract_text_nonempty` to
+/// treat that as an error instead.
 ///
 /// # Errors
 ///
 /// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
 /// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
 /// ```
 pub fn extract_text(pdf_bytes: &[u8]) -> Result<String> {
-    // Ensure PDFium is initialized
-    initialize()?;
-
-    if pdf_bytes.is_empty() {
-        return Err(PdfiumError::InvalidData);
-    }
-
-    unsafe {
-        // Load PDF directly with PDFium
-        let doc = ffi::FPDF_LoadMemDocument(
-            pdf_bytes.as_ptr() as *const std::ffi::c_void,
-            pdf_bytes.len() as i32,
-            std::ptr::null(),
-        );
-
-        if doc.is_null() {
-            return Err(PdfiumError::ExtractionFailed(
-                "Failed to load PDF document".to_string()
-            ));
-        }
+    // Load PDF directly with PDFium (initializes the library if needed)
+    let doc = Document::load(pdf_bytes)?;
+    join_page_text(&doc, 0..doc.page_count())
+}
 
-        let page_count = ffi::FPDF_GetPageCount(doc);
-        let mut text = String::new();
+/// What `extract_text_on_error` does with a page that fails to load
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OnPageError {
+    /// Leave the page's text empty (what `extract_text` does)
+    Skip,
+    /// Stop and return an error naming the page
+    Abort,
+    /// Insert this text in place of the page's text
+    Placeholder(String),
+}
 
-        // Extract text from each page
-        for i in 0..page_count {
-            let page = ffi::FPDF_LoadPage(doc, i);
-            if page.is_null() {
-                continue;
-            }
+/// Extract text from a PDF document, choosing how page failures are handled
+///
+/// A page fails when PDFium cannot load it or its text layer. Under
+/// `OnPageError::Skip` the output matches `extract_text`, which also drops the
+/// separator after a page that fails to load; the other policies keep one
+/// separator between every pair of pages.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `policy` - How to treat pages that fail to load
+///
+/// # Returns
+///
+/// Returns the extracted text, pages separated as in `extract_text`.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed, or
+/// naming the first failed page under `OnPageError::Abort`.
+pub fn extract_text_on_error(pdf_bytes: &[u8], policy: OnPageError) -> Result<String> {
+    let doc = Document::load(pdf_bytes)?;
+    let page_count = doc.page_count();
+    let mut text = String::new();
 
-            let text_page = ffi::FPDFText_LoadPage(page);
-            if !text_page.is_null() {
-                let text_length = ffi::FPDFText_CountChars(text_page);
-
-                if text_length > 0 {
-                    // Allocate buffer for UTF-16 text
-                    let mut buffer: Vec<u16> = vec![0; (text_length + 1) as usize];
-                    let chars_written = ffi::FPDFText_GetText(
-                        text_page,
-                        0,
-                        text_length,
-                        buffer.as_mut_ptr(),
-                    );
-
-                    if chars_written > 0 {
-                        // Convert UTF-16 to Rust String
-                        buffer.truncate((chars_written - 1) as usize);
-                        text.push_str(&String::from_utf16_lossy(&buffer));
-                    }
+    for i in 0..page_count {
+        let page = doc.try_page(i);
+        match page.as_ref().and_then(|page| page.text()) {
+            Some(text_page) => text_page.append_text(&mut text)?,
+            None => match &policy {
+                // Like extract_text, a page that fails to load gets no separator
+                OnPageError::Skip if page.is_none() => continue,
+                OnPageError::Skip => {}
+                OnPageError::Abort => {
+                    return Err(PdfiumError::ExtractionFailed(format!(
+                        "Failed to load page {}",
+                        i
+                    )))
                 }
-
-                ffi::FPDFText_ClosePage(text_page);
-            }
-
-            ffi::FPDF_ClosePage(page);
-
-            // Add page separator
-            if i < page_count - 1 {
-                text.push_str("\n---PAGE BREAK---\n");
-            }
+                OnPageError::Placeholder(marker) => text.push_str(marker),
+            },
         }
 
-        ffi::FPDF_CloseDocument(doc);
-        Ok(text)
-    }
-}
-
-/// Extract text from a PDF document (C ABI for WASM)
-/// Returns pointer to null-terminated UTF-8 string, or null on error
-/// Caller must free the returned string with pdfium_wasm_free_string
-#[no_mangle]
-pub extern "C" fn pdfium_wasm_extract_text(
-    pdf_data: *const u8,
-    pdf_len: usize,
-) -> *mut u8 {
-    if pdf_data.is_null() || pdf_len == 0 {
-        return std::ptr::null_mut();
-    }
-
-    let pdf_bytes = unsafe { std::slice::from_raw_parts(pdf_data, pdf_len) };
-
-    match extract_text(pdf_bytes) {
-        Ok(text) => {
-            let c_string = std::ffi::CString::new(text).unwrap_or_default();
-            c_string.into_raw() as *mut u8
+        if i + 1 < page_count {
+            text.push_str(PAGE_SEPARATOR);
         }
-        Err(_) => std::ptr::null_mut(),
     }
+
+    Ok(text)
 }
 
-/// Convert a PDF document to JSON format using QPDF
+/// Extract text from a PDF document into a caller-provided buffer
+///
+/// Produces the same text as `extract_text`, but clears and refills `out`
+/// instead of allocating a new `String`, so a loop over many documents keeps
+/// reusing one allocation. The per-page UTF-16 staging buffer is likewise
+/// shared across calls on the same thread.
 ///
 /// # Arguments
 ///
 /// * `pdf_bytes` - The PDF document as a byte slice
+/// * `out` - Buffer that receives the text; its previous contents are discarded
 ///
 /// # Returns
 ///
-/// Returns the PDF structure as a JSON string (version 2 format with comprehensive details).
+/// Returns `Ok(())` with the text in `out`.
 ///
 /// # Errors
 ///
 /// Returns `PdfiumError::InvalidData` if the input is empty.
-/// Returns `PdfiumError::ConversionFailed` if the PDF cannot be converted.
-/// ```
-pub fn pdf_to_json(pdf_bytes: &[u8]) -> Result<String> {
-    // Ensure PDFium is initialized
-    initialize()?;
-
-    if pdf_bytes.is_empty() {
-        return Err(PdfiumError::InvalidData);
-    }
-
-    unsafe {
-        // Call QPDF directly
-        let json_ptr = ffi::IPDF_QPDF_PDFToJSON(
-            pdf_bytes.as_ptr() as *const std::ffi::c_void,
-            pdf_bytes.len(),
-            2, // Version 2
-        );
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+/// On error `out` is empty or holds the text of the pages before the failure.
+pub fn extract_text_into(pdf_bytes: &[u8], out: &mut String) -> Result<()> {
+    out.clear();
+    let doc = Document::load(pdf_bytes)?;
+    append_page_text(&doc, 0..doc.page_count(), out)
+}
+
+/// Extract text from a PDF document, separating pages with a form feed
+///
+/// Same as `extract_text` but with a single `\f` (U+000C) between pages, the
+/// page delimiter `pdftotext` and most text-processing tools expect. Unlike
+/// `pdftotext`, no form feed follows the last page.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the extracted text with pages joined by `\f`.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+pub fn extract_text_formfeed(pdf_bytes: &[u8]) -> Result<String> {
+    let doc = Document::load(pdf_bytes)?;
+    let mut text = String::new();
+    append_page_text_separated(&doc, 0..doc.page_count(), "\u{000C}", &mut text)?;
+    Ok(text)
+}
+
+/// Extract text from a PDF document, rejecting documents without pages
+///
+/// Identical to `extract_text` except for the zero-page case, which a stub or
+/// truncated file can produce while still parsing as a valid PDF.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the extracted text, pages separated as in `extract_text`.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::NoPages` if the document has no pages.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+pub fn extract_text_nonempty(pdf_bytes: &[u8]) -> Result<String> {
+    let doc = Document::load(pdf_bytes)?;
+    if doc.page_count() == 0 {
+        return Err(PdfiumError::NoPages);
+    }
+    join_page_text(&doc, 0..doc.page_count())
+}
+
+/// Extract text from a PDF document along with where each page starts
+///
+/// The text is identical to `extract_text`. Each offset points just past the
+/// preceding page separator, so page `i` spans `offsets[i]` up to the start of
+/// the next separator (or the end of the string). A binary search over the
+/// offsets maps any byte position back to its page.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns `(text, offsets)` with one starting byte offset per page, in page
+/// order. Pages that fail to load still get an offset, where the next page
+/// starts (they contribute neither text nor a separator).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+pub fn extract_text_with_offsets(pdf_bytes: &[u8]) -> Result<(String, Vec<usize>)> {
+    let doc = Document::load(pdf_bytes)?;
+    let page_count = doc.page_count();
+    let mut text = String::new();
+    let mut offsets = Vec::with_capacity(page_count);
+
+    for i in 0..page_count {
+        offsets.push(text.len());
+
+        // As in extract_text, a page that fails to load gets no separator
+        if let Some(page) = doc.try_page(i) {
+            if let Some(text_page) = page.text() {
+                text_page.append_text(&mut text)?;
+            }
+
+            if i + 1 < page_count {
+                text.push_str(PAGE_SEPARATOR);
+            }
+        }
+    }
+
+    Ok((text, offsets))
+}
+
+/// Extract text as JSON lines, one object per page
+///
+/// Each line is `{"page":N,"text":"..."}` with a zero-based page number,
+/// followed by `\n`. Pages without text, including pages that fail to load,
+/// still get a line so the numbering has no gaps.
+///
+/// Requires the `jsonl` feature, as the lines are encoded with serde_json.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the newline-delimited JSON (empty for a document without pages).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+#[cfg(feature = "jsonl")]
+pub fn extract_text_jsonl(pdf_bytes: &[u8]) -> Result<String> {
+    let doc = Document::load(pdf_bytes)?;
+    let mut jsonl = String::new();
+    let mut page_text = String::new();
+
+    for i in 0..doc.page_count() {
+        page_text.clear();
+        if let Some(page) = doc.try_page(i) {
+            if let Some(text_page) = page.text() {
+                text_page.append_text(&mut page_text)?;
+            }
+        }
+
+        let line = serde_json::json!({ "page": i, "text": page_text });
+        jsonl.push_str(&line.to_string());
+        jsonl.push('\n');
+    }
+
+    Ok(jsonl)
+}
+
+/// Extract text from many PDF documents, one result per document
+///
+/// A document that fails does not stop the batch: its slot holds the error and
+/// the remaining documents are still processed. The library is initialized
+/// once before the first document.
+///
+/// # Arguments
+///
+/// * `docs` - The PDF documents as byte slices
+///
+/// # Returns
+///
+/// Returns a `Vec` with the `extract_text` result of each document, in input
+/// order. If PDFium cannot be initialized, every slot holds that error.
+pub fn extract_text_batch(docs: &[&[u8]]) -> Vec<Result<String>> {
+    if let Err(e) = initialize() {
+        return docs.iter().map(|_| Err(e.clone())).collect();
+    }
+
+    docs.iter().map(|pdf_bytes| extract_text(pdf_bytes)).collect()
+}
+
+/// Separator inserted between pages by `extract_text` and friends
+const PAGE_SEPARATOR: &str = "\n---PAGE BREAK---\n";
+
+/// Concatenate the text of the given pages, skipping pages that fail to load
+fn join_page_text(doc: &Document, pages: std::ops::Range<usize>) -> Result<String> {
+    let mut text = String::new();
+    append_page_text(doc, pages, &mut text)?;
+    Ok(text)
+}
+
+/// Append the text of the given pages to `out`, as `join_page_text` does
+fn append_page_text(doc: &Document, pages: std::ops::Range<usize>, out: &mut String) -> Result<()> {
+    append_page_text_separated(doc, pages, PAGE_SEPARATOR, out)
+}
+
+/// Append the text of the given pages to `out`, with `separator` between pages
+fn append_page_text_separated(
+    doc: &Document,
+    pages: std::ops::Range<usize>,
+    separator: &str,
+    out: &mut String,
+) -> Result<()> {
+    // Extract text from each page
+    for i in pages.clone() {
+        // A page that fails to load contributes neither text nor a separator
+        if let Some(page) = doc.try_page(i) {
+            if let Some(text_page) = page.text() {
+                text_page.append_text(out)?;
+            }
+
+            // Add page separator
+            if i + 1 < pages.end {
+                out.push_str(separator);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Extract text from a contiguous range of pages
+///
+/// Only the pages in `[start, end)` are loaded, so this is much cheaper than
+/// calling `extract_text` and slicing the result.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `start` - First page to extract (zero-based, inclusive)
+/// * `end` - Page to stop at (exclusive)
+///
+/// # Returns
+///
+/// Returns the text of the range, pages separated by "---PAGE BREAK---".
+/// An empty range yields an empty string.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if `end > page_count` or `start > end`.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+pub fn extract_text_range(pdf_bytes: &[u8], start: usize, end: usize) -> Result<String> {
+    let doc = Document::load(pdf_bytes)?;
+
+    let page_count = doc.page_count();
+    if end > page_count {
+        return Err(PdfiumError::PageOutOfRange { index: end, page_count });
+    }
+    if start > end {
+        return Err(PdfiumError::PageOutOfRange { index: start, page_count });
+    }
+
+    join_page_text(&doc, start..end)
+}
+
+/// Extract at most `max_chars` characters of text from a PDF document
+///
+/// Pages are extracted in order and no further pages are loaded once the
+/// budget is reached, so previews of huge documents stay cheap.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `max_chars` - Maximum length of the result in `char`s (page separators count)
+///
+/// # Returns
+///
+/// Returns the start of `extract_text`'s output, cut on a `char` boundary.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+pub fn extract_text_capped(pdf_bytes: &[u8], max_chars: usize) -> Result<String> {
+    let doc = Document::load(pdf_bytes)?;
+    let page_count = doc.page_count();
+    let mut text = String::new();
+    let mut chars = 0;
+
+    for i in 0..page_count {
+        if chars >= max_chars {
+            break;
+        }
+        // As in extract_text, a page that fails to load gets no separator
+        let Some(page) = doc.try_page(i) else {
+            continue;
+        };
+        if let Some(text_page) = page.text() {
+            let page_text = text_page.text()?;
+            chars += page_text.chars().count();
+            text.push_str(&page_text);
+        }
+        if i + 1 < page_count {
+            text.push_str(PAGE_SEPARATOR);
+            chars += PAGE_SEPARATOR.chars().count();
+        }
+    }
+
+    if let Some((cut, _)) = text.char_indices().nth(max_chars) {
+        text.truncate(cut);
+    }
+    Ok(text)
+}
+
+/// Extract text from a PDF document with whitespace cleaned up for NLP
+///
+/// Applies these rules, in order, to the output of `extract_text`:
+///
+/// 1. `\r\n` and lone `\r` become `\n`.
+/// 2. Within each line, runs of spaces and tabs collapse to one space, and
+///    leading and trailing spaces are removed.
+/// 3. A line ending in a letter followed by `-` is joined to the next line,
+///    without the hyphen, when the next line starts with a lowercase letter
+///    (`exam-` / `ple` becomes `example`). Genuine compounds split at a line
+///    end (`well-` / `known`) are joined the same way.
+///
+/// Everything else, including blank lines and page separators, is kept as is.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the normalized text, pages separated by "---PAGE BREAK---".
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+pub fn extract_text_normalized(pdf_bytes: &[u8]) -> Result<String> {
+    extract_text(pdf_bytes).map(|text| normalize_text(&text))
+}
+
+/// The normalization described on `extract_text_normalized`
+fn normalize_text(text: &str) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let mut out = String::with_capacity(text.len());
+
+    for (i, line) in text.split('\n').enumerate() {
+        let line = line
+            .split([' ', '\t'])
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if i > 0 {
+            let mut tail = out.chars().rev();
+            let hyphenated = tail.next() == Some('-') && tail.next().is_some_and(char::is_alphabetic);
+            if hyphenated && line.starts_with(char::is_lowercase) {
+                out.pop();
+            } else {
+                out.push('\n');
+            }
+        }
+        out.push_str(&line);
+    }
+
+    out
+}
+
+/// Extract text from a PDF file on disk
+///
+/// Requires the `std-io` feature. Reads the whole file and calls `extract_text`.
+///
+/// # Arguments
+///
+/// * `path` - Path to the PDF file
+///
+/// # Returns
+///
+/// Returns the extracted text, pages separated by "---PAGE BREAK---".
+///
+/// # Errors
+///
+/// Returns `PdfiumError::Io` if the file cannot be read.
+/// Returns `PdfiumError::InvalidData` if the file is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+#[cfg(feature = "std-io")]
+pub fn extract_text_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<String> {
+    let path = path.as_ref();
+    let pdf_bytes = std::fs::read(path)
+        .map_err(|e| PdfiumError::Io(format!("{}: {}", path.display(), e)))?;
+    extract_text(&pdf_bytes)
+}
+
+/// Wall-clock breakdown of an `extract_text_timed` call
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtractTiming {
+    /// Time spent loading the document (including library initialization)
+    pub load_ms: f64,
+    /// Time spent extracting page text
+    pub extract_ms: f64,
+    /// Number of pages in the document
+    pub pages: usize,
+}
+
+/// Extract text from a PDF document and report where the time went
+///
+/// Same output as `extract_text`. Timings use `std::time::Instant`, which
+/// Emscripten backs with `performance.now()` in the browser.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the extracted text and an `ExtractTiming`.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+pub fn extract_text_timed(pdf_bytes: &[u8]) -> Result<(String, ExtractTiming)> {
+    let start = std::time::Instant::now();
+    let doc = Document::load(pdf_bytes)?;
+    let pages = doc.page_count();
+    let loaded = std::time::Instant::now();
+
+    let text = join_page_text(&doc, 0..pages)?;
+    let extracted = std::time::Instant::now();
+
+    let timing = ExtractTiming {
+        load_ms: (loaded - start).as_secs_f64() * 1000.0,
+        extract_ms: (extracted - loaded).as_secs_f64() * 1000.0,
+        pages,
+    };
+    Ok((text, timing))
+}
+
+/// Extract text page by page, handing each page to a callback
+///
+/// Only one page's text is held in memory at a time. Pages that fail to load
+/// are reported with empty text so indices stay contiguous.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `callback` - Called with `(page_index, text)`; return `false` to stop early
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+pub fn for_each_page_text<F: FnMut(usize, &str) -> bool>(
+    pdf_bytes: &[u8],
+    mut callback: F,
+) -> Result<()> {
+    let doc = Document::load(pdf_bytes)?;
+
+    for i in 0..doc.page_count() {
+        let text = match doc.try_page(i) {
+            Some(page) => match page.text() {
+                Some(text_page) => text_page.text()?,
+                None => String::new(),
+            },
+            None => String::new(),
+        };
+
+        if !callback(i, &text) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Extracted text plus a per-document account of how complete it is
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractReport {
+    /// Same output as `extract_text`
+    pub text: String,
+    pub total_pages: usize,
+    /// Pages whose text contains anything besides whitespace
+    pub pages_with_text: usize,
+    /// Pages PDFium could not load, or could not build a text layer for
+    pub failed_pages: Vec<usize>,
+}
+
+/// Extract text from a PDF document and report which pages contributed
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns an `ExtractReport`; failed pages contribute empty text.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+pub fn extract_text_report(pdf_bytes: &[u8]) -> Result<ExtractReport> {
+    let doc = Document::load(pdf_bytes)?;
+    let mut report = ExtractReport {
+        total_pages: doc.page_count(),
+        ..ExtractReport::default()
+    };
+
+    for i in 0..report.total_pages {
+        let Some(page) = doc.try_page(i) else {
+            // As in extract_text, a page that fails to load gets no separator
+            report.failed_pages.push(i);
+            continue;
+        };
+
+        match page.text() {
+            Some(text_page) => {
+                let page_text = text_page.text()?;
+                if !page_text.trim().is_empty() {
+                    report.pages_with_text += 1;
+                }
+                report.text.push_str(&page_text);
+            }
+            None => report.failed_pages.push(i),
+        }
+
+        if i + 1 < report.total_pages {
+            report.text.push_str(PAGE_SEPARATOR);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Extract text from a PDF document (C ABI for WASM)
+/// Returns pointer to null-terminated UTF-8 string, or null on error
+/// Caller must free the returned string with pdfium_wasm_free_string
+#[no_mangle]
+pub extern "C" fn pdfium_wasm_extract_text(
+    pdf_data: *const u8,
+    pdf_len: usize,
+) -> *mut u8 {
+    if pdf_data.is_null() || pdf_len == 0 {
+        return std::ptr::null_mut();
+    }
+
+    let pdf_bytes = unsafe { std::slice::from_raw_parts(pdf_data, pdf_len) };
+
+    ffi_guard(std::ptr::null_mut(), || match extract_text(pdf_bytes) {
+        Ok(text) => into_c_string(text),
+        Err(_) => std::ptr::null_mut(),
+    })
+}
+
+/// Extract text from a base64-encoded PDF document
+///
+/// Requires the `base64` feature. Uses the standard alphabet with padding;
+/// ASCII whitespace (such as line wrapping) is ignored.
+///
+/// # Arguments
+///
+/// * `b64` - The PDF document, base64-encoded
+///
+/// # Returns
+///
+/// Returns the extracted text, as `extract_text` does.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if `b64` is not valid base64 or decodes to nothing.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+#[cfg(feature = "base64")]
+pub fn extract_text_base64(b64: &str) -> Result<String> {
+    use base64::Engine;
+
+    let compact: String = b64.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    let pdf_bytes = base64::engine::general_purpose::STANDARD
+        .decode(compact)
+        .map_err(|_| PdfiumError::InvalidData)?;
+    extract_text(&pdf_bytes)
+}
+
+/// Extract text from a base64-encoded PDF document (C ABI for WASM)
+/// Takes a null-terminated base64 string
+/// Returns pointer to null-terminated UTF-8 string, or null on error
+/// Caller must free the returned string with pdfium_wasm_free_string
+///
+/// # Safety
+/// `b64` must be null or point to a null-terminated string.
+#[cfg(feature = "base64")]
+#[no_mangle]
+pub unsafe extern "C" fn pdfium_wasm_extract_text_base64(
+    b64: *const std::os::raw::c_char,
+) -> *mut u8 {
+    if b64.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    ffi_guard(std::ptr::null_mut(), || {
+        // Base64 is ASCII, so anything that isn't UTF-8 is invalid anyway
+        let Ok(b64) = std::ffi::CStr::from_ptr(b64).to_str() else {
+            return std::ptr::null_mut();
+        };
+        match extract_text_base64(b64) {
+            Ok(text) => into_c_string(text),
+            Err(_) => std::ptr::null_mut(),
+        }
+    })
+}
+
+/// Convert a PDF document to JSON format using QPDF
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the PDF structure as a JSON string (version 2 format with comprehensive details).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::ConversionFailed` if the PDF cannot be converted,
+/// including when QPDF returns an empty string or an empty JSON object.
+/// ```
+#[cfg(feature = "qpdf")]
+pub fn pdf_to_json(pdf_bytes: &[u8]) -> Result<String> {
+    with_qpdf_json(pdf_bytes, |json| Ok(String::from_utf8_lossy(json).into_owned()))
+}
+
+/// Convert a PDF document to JSON format using QPDF, writing it to `writer`
+///
+/// Produces the same JSON as `pdf_to_json`, but copies it to `writer` in
+/// chunks straight from QPDF's buffer instead of building a `String`, which
+/// saves one full-size copy. QPDF still generates the whole document into a
+/// single buffer first, so peak memory stays proportional to the JSON size.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `writer` - Destination for the JSON bytes
+///
+/// # Returns
+///
+/// Returns `Ok(())` once all the JSON has been written.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::ConversionFailed` if the PDF cannot be converted,
+/// including when QPDF returns an empty string or an empty JSON object.
+/// Returns `PdfiumError::Io` if writing fails; part of the JSON may have been written.
+#[cfg(feature = "qpdf")]
+pub fn pdf_to_json_writer<W: std::io::Write>(pdf_bytes: &[u8], mut writer: W) -> Result<()> {
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    with_qpdf_json(pdf_bytes, |json| {
+        for chunk in json.chunks(CHUNK_SIZE) {
+            writer
+                .write_all(chunk)
+                .map_err(|e| PdfiumError::Io(e.to_string()))?;
+        }
+        writer.flush().map_err(|e| PdfiumError::Io(e.to_string()))
+    })
+}
+
+/// Run QPDF's JSON conversion and pass the output to `f` before freeing it
+#[cfg(feature = "qpdf")]
+fn with_qpdf_json<T>(pdf_bytes: &[u8], f: impl FnOnce(&[u8]) -> Result<T>) -> Result<T> {
+    // Ensure PDFium is initialized
+    initialize()?;
+
+    if pdf_bytes.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+    check_document_size(pdf_bytes.len())?;
+
+    unsafe {
+        // Call QPDF directly
+        let json_ptr = ffi::IPDF_QPDF_PDFToJSON(
+            pdf_bytes.as_ptr() as *const std::ffi::c_void,
+            pdf_bytes.len(),
+            2, // Version 2
+        );
 
         if json_ptr.is_null() {
             return Err(PdfiumError::ConversionFailed(
@@ -335,64 +1685,5971 @@ pub fn pdf_to_json(pdf_bytes: &[u8]) -> Result<String> {
             ));
         }
 
-        // Convert C string to Rust String
-        let c_str = std::ffi::CStr::from_ptr(json_ptr);
-        let json = c_str.to_string_lossy().into_owned();
+        let json = std::ffi::CStr::from_ptr(json_ptr).to_bytes();
+        let result = if is_empty_json(json) {
+            Err(PdfiumError::ConversionFailed("empty QPDF output".to_string()))
+        } else {
+            f(json)
+        };
+
+        // Free the C string using QPDF's function
+        ffi::IPDF_QPDF_FreeString(json_ptr);
+
+        result
+    }
+}
+
+/// Whether QPDF output is blank or just `{}` (which it can return for broken input)
+#[cfg(feature = "qpdf")]
+fn is_empty_json(json: &[u8]) -> bool {
+    let trimmed = json.trim_ascii();
+    match trimmed.strip_prefix(b"{").and_then(|rest| rest.strip_suffix(b"}")) {
+        Some(inner) => inner.trim_ascii().is_empty(),
+        None => trimmed.is_empty(),
+    }
+}
+
+/// Convert a PDF document to JSON format using QPDF (C ABI for WASM)
+/// Returns pointer to null-terminated UTF-8 string, or null on error
+/// Caller must free the returned string with pdfium_wasm_free_string
+#[cfg(feature = "qpdf")]
+#[no_mangle]
+pub extern "C" fn pdfium_wasm_pdf_to_json(
+    pdf_data: *const u8,
+    pdf_len: usize,
+) -> *mut u8 {
+    if pdf_data.is_null() || pdf_len == 0 {
+        return std::ptr::null_mut();
+    }
+
+    let pdf_bytes = unsafe { std::slice::from_raw_parts(pdf_data, pdf_len) };
+
+    ffi_guard(std::ptr::null_mut(), || match pdf_to_json(pdf_bytes) {
+        Ok(json) => into_c_string(json),
+        Err(_) => std::ptr::null_mut(),
+    })
+}
+
+/// Convert a PDF document to JSON using QPDF and parse the result
+///
+/// Requires the `json-typed` feature. Use `pdf_to_json` for the raw string.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the parsed QPDF JSON (version 2 format) as a `serde_json::Value`.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ConversionFailed` if the PDF cannot be converted.
+/// Returns `PdfiumError::MalformedJson` if QPDF's output does not parse.
+#[cfg(feature = "json-typed")]
+pub fn pdf_to_json_value(pdf_bytes: &[u8]) -> Result<serde_json::Value> {
+    let json = pdf_to_json(pdf_bytes)?;
+    parse_qpdf_json(&json)
+}
+
+/// Parse QPDF JSON output, tolerating stray trailing NULs and whitespace
+#[cfg(feature = "json-typed")]
+fn parse_qpdf_json(json: &str) -> Result<serde_json::Value> {
+    let trimmed = json.trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+    serde_json::from_str(trimmed).map_err(|e| PdfiumError::MalformedJson(e.to_string()))
+}
+
+/// Look up an indirect object (`"N G R"`, or `"trailer"`) in QPDF JSON output
+///
+/// Version 2 keeps objects under `qpdf[1]` keyed `"obj:N G R"`; version 1 keeps
+/// them under `objects` keyed by the plain reference.
+#[cfg(feature = "json-typed")]
+fn qpdf_json_object<'v>(json: &'v serde_json::Value, reference: &str) -> Option<&'v serde_json::Value> {
+    if let Some(objects) = json.get("qpdf").and_then(|q| q.get(1)) {
+        let key = if reference == "trailer" {
+            reference.to_string()
+        } else {
+            format!("obj:{}", reference)
+        };
+        return objects.get(key);
+    }
+    json.get("objects").and_then(|objects| objects.get(reference))
+}
+
+/// The dictionary of an object entry, unwrapping v2's `value` / `stream.dict` wrappers
+#[cfg(feature = "json-typed")]
+fn qpdf_json_dict(entry: &serde_json::Value) -> Option<&serde_json::Map<String, serde_json::Value>> {
+    entry
+        .get("value")
+        .or_else(|| entry.get("stream").and_then(|stream| stream.get("dict")))
+        .unwrap_or(entry)
+        .as_object()
+}
+
+/// Maximum page tree nesting followed when resolving pages from QPDF JSON
+#[cfg(feature = "json-typed")]
+const MAX_PAGE_TREE_DEPTH: usize = 64;
+
+/// Collect page object references in document order by walking `/Kids`
+#[cfg(feature = "json-typed")]
+fn collect_page_refs(json: &serde_json::Value, node: &str, depth: usize, pages: &mut Vec<String>) {
+    if depth > MAX_PAGE_TREE_DEPTH {
+        return;
+    }
+    let Some(dict) = qpdf_json_object(json, node).and_then(qpdf_json_dict) else {
+        return;
+    };
+
+    match dict.get("/Kids").and_then(|kids| kids.as_array()) {
+        Some(kids) => {
+            for kid in kids.iter().filter_map(|kid| kid.as_str()) {
+                collect_page_refs(json, kid, depth + 1, pages);
+            }
+        }
+        None => pages.push(node.to_string()),
+    }
+}
+
+/// Object references of every page, from the `pages` summary or the page tree
+#[cfg(feature = "json-typed")]
+fn qpdf_json_page_refs(json: &serde_json::Value) -> Vec<String> {
+    if let Some(pages) = json.get("pages").and_then(|pages| pages.as_array()) {
+        return pages
+            .iter()
+            .filter_map(|page| page.get("object").and_then(|o| o.as_str()))
+            .map(str::to_string)
+            .collect();
+    }
+
+    let mut pages = Vec::new();
+    let root = qpdf_json_object(json, "trailer")
+        .and_then(qpdf_json_dict)
+        .and_then(|trailer| trailer.get("/Root"))
+        .and_then(|root| root.as_str());
+    let tree = root
+        .and_then(|root| qpdf_json_object(json, root))
+        .and_then(qpdf_json_dict)
+        .and_then(|catalog| catalog.get("/Pages"))
+        .and_then(|tree| tree.as_str());
+    if let Some(tree) = tree {
+        collect_page_refs(json, tree, 0, &mut pages);
+    }
+    pages
+}
+
+/// Convert a PDF to QPDF JSON and return only one page's object
+///
+/// Requires the `json-typed` feature. The full document is still converted;
+/// only the returned string is small.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the page object's entry from the QPDF JSON (e.g. `{"value": {"/Type": "/Page", ...}}`).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ConversionFailed` if the PDF cannot be converted or
+/// the page object cannot be resolved.
+/// Returns `PdfiumError::MalformedJson` if QPDF's output does not parse.
+#[cfg(feature = "json-typed")]
+pub fn page_to_json(pdf_bytes: &[u8], page_index: usize) -> Result<String> {
+    let json = pdf_to_json_value(pdf_bytes)?;
+
+    let pages = qpdf_json_page_refs(&json);
+    if pages.is_empty() {
+        return Err(PdfiumError::ConversionFailed(
+            "Could not resolve pages in QPDF JSON".to_string()
+        ));
+    }
+    let reference = pages.get(page_index).ok_or(PdfiumError::PageOutOfRange {
+        index: page_index,
+        page_count: pages.len(),
+    })?;
+
+    let page = qpdf_json_object(&json, reference).ok_or_else(|| {
+        PdfiumError::ConversionFailed(format!("QPDF JSON has no object {}", reference))
+    })?;
+    Ok(page.to_string())
+}
+
+/// Convert a PDF to QPDF JSON and return only the trailer dictionary
+///
+/// Requires the `json-typed` feature. Handy for inspecting the `/Root`, `/Info`
+/// and `/ID` entries without shipping the full JSON.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the `"trailer"` entry from the QPDF JSON (e.g. `{"value": {"/Root": "1 0 R", ...}}`).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ConversionFailed` if the PDF cannot be converted or the
+/// JSON has no trailer.
+/// Returns `PdfiumError::MalformedJson` if QPDF's output does not parse.
+#[cfg(feature = "json-typed")]
+pub fn trailer_json(pdf_bytes: &[u8]) -> Result<String> {
+    let json = pdf_to_json_value(pdf_bytes)?;
+    let trailer = qpdf_json_object(&json, "trailer").ok_or_else(|| {
+        PdfiumError::ConversionFailed("QPDF JSON has no trailer".to_string())
+    })?;
+    Ok(trailer.to_string())
+}
+
+/// Convert a PDF to QPDF JSON and return a single indirect object
+///
+/// Requires the `json-typed` feature. Works with both the v2 layout
+/// (`"obj:N G R"` keys) and the v1 `"objects"` map.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `obj_num` - Object number
+/// * `gen_num` - Generation number (usually 0)
+///
+/// # Returns
+///
+/// Returns the object's entry from the QPDF JSON (e.g. `{"value": {"/Type": "/Catalog", ...}}`).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ConversionFailed` if the PDF cannot be converted or the
+/// object does not exist.
+/// Returns `PdfiumError::MalformedJson` if QPDF's output does not parse.
+#[cfg(feature = "json-typed")]
+pub fn get_object_json(pdf_bytes: &[u8], obj_num: u32, gen_num: u16) -> Result<String> {
+    let json = pdf_to_json_value(pdf_bytes)?;
+    let reference = format!("{} {} R", obj_num, gen_num);
+    let object = qpdf_json_object(&json, &reference).ok_or_else(|| {
+        PdfiumError::ConversionFailed(format!("QPDF JSON has no object {}", reference))
+    })?;
+    Ok(object.to_string())
+}
+
+/// Stream size totals of a PDF, from `size_analysis`
+#[cfg(feature = "json-typed")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SizeAnalysis {
+    /// Size of the input in bytes
+    pub file_size: usize,
+    pub stream_count: usize,
+    /// Sum of the streams' encoded `/Length`s
+    pub compressed_stream_bytes: usize,
+    /// Estimated sum of the streams' decoded sizes
+    pub uncompressed_stream_bytes: usize,
+}
+
+/// Read an integer entry of a QPDF JSON dictionary, following an indirect reference
+#[cfg(feature = "json-typed")]
+fn qpdf_json_int(
+    json: &serde_json::Value,
+    dict: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Option<u64> {
+    match dict.get(key)? {
+        serde_json::Value::String(reference) => qpdf_json_object(json, reference)?
+            .get("value")?
+            .as_u64(),
+        value => value.as_u64(),
+    }
+}
+
+/// Number of colour components of an image's `/ColorSpace`, if known
+#[cfg(feature = "json-typed")]
+fn qpdf_json_color_components(json: &serde_json::Value, color_space: &serde_json::Value) -> Option<u64> {
+    let (name, params) = match color_space {
+        serde_json::Value::String(name) if name.ends_with(" R") => {
+            let resolved = qpdf_json_object(json, name)?.get("value")?;
+            return qpdf_json_color_components(json, resolved);
+        }
+        serde_json::Value::String(name) => (name.as_str(), None),
+        serde_json::Value::Array(family) => (family.first()?.as_str()?, family.get(1)),
+        _ => return None,
+    };
+
+    match name {
+        "/DeviceGray" | "/CalGray" | "/Indexed" | "/Separation" => Some(1),
+        "/DeviceRGB" | "/CalRGB" | "/Lab" => Some(3),
+        "/DeviceCMYK" => Some(4),
+        "/ICCBased" => {
+            let profile = qpdf_json_object(json, params?.as_str()?).and_then(qpdf_json_dict)?;
+            qpdf_json_int(json, profile, "/N")
+        }
+        "/DeviceN" => params?.as_array().map(|names| names.len() as u64),
+        _ => None,
+    }
+}
+
+/// Decoded size of an image XObject from its dimensions, if it is one
+///
+/// Colour spaces that can't be resolved count as RGB.
+#[cfg(feature = "json-typed")]
+fn qpdf_json_image_size(
+    json: &serde_json::Value,
+    dict: &serde_json::Map<String, serde_json::Value>,
+) -> Option<u64> {
+    if dict.get("/Subtype").and_then(|s| s.as_str()) != Some("/Image") {
+        return None;
+    }
+    let width = qpdf_json_int(json, dict, "/Width")?;
+    let height = qpdf_json_int(json, dict, "/Height")?;
+
+    let (components, bits) = if dict.get("/ImageMask").and_then(|m| m.as_bool()) == Some(true) {
+        (1, 1)
+    } else {
+        let components = dict
+            .get("/ColorSpace")
+            .and_then(|cs| qpdf_json_color_components(json, cs))
+            .unwrap_or(3);
+        (components, qpdf_json_int(json, dict, "/BitsPerComponent").unwrap_or(8))
+    };
+
+    // Rows are padded to whole bytes
+    let row = width.checked_mul(components)?.checked_mul(bits)?.div_ceil(8);
+    row.checked_mul(height)
+}
+
+/// Estimate how much a PDF's streams expand when decoded
+///
+/// Requires the `json-typed` feature. Every figure comes from the stream
+/// dictionaries in the QPDF JSON, so no stream is decoded. A stream's encoded
+/// size is its `/Length` (zero if missing). Its decoded size is its `/DL` entry
+/// when present, its encoded size when it has no filter, the pixel size from
+/// `/Width`, `/Height` and `/BitsPerComponent` for images, and otherwise its
+/// encoded size, so compressed non-image streams without `/DL` are undercounted.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns a `SizeAnalysis` with the stream count and encoded and decoded totals.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::ConversionFailed` if the PDF cannot be converted, or
+/// the JSON is not in the version 2 layout.
+/// Returns `PdfiumError::MalformedJson` if QPDF's output does not parse.
+#[cfg(feature = "json-typed")]
+pub fn size_analysis(pdf_bytes: &[u8]) -> Result<SizeAnalysis> {
+    let json = pdf_to_json_value(pdf_bytes)?;
+    size_analysis_of_json(&json, pdf_bytes.len())
+}
+
+/// The stream totals of `size_analysis`, from already-parsed QPDF JSON
+#[cfg(feature = "json-typed")]
+fn size_analysis_of_json(json: &serde_json::Value, file_size: usize) -> Result<SizeAnalysis> {
+    let objects = json
+        .get("qpdf")
+        .and_then(|q| q.get(1))
+        .and_then(|o| o.as_object())
+        .ok_or_else(|| {
+            PdfiumError::ConversionFailed("QPDF JSON has no version 2 object map".to_string())
+        })?;
+
+    let mut analysis = SizeAnalysis {
+        file_size,
+        ..SizeAnalysis::default()
+    };
+
+    for entry in objects.values() {
+        let Some(dict) = entry
+            .get("stream")
+            .and_then(|stream| stream.get("dict"))
+            .and_then(|dict| dict.as_object())
+        else {
+            continue;
+        };
+
+        let encoded = qpdf_json_int(json, dict, "/Length").unwrap_or(0);
+        let has_filter = match dict.get("/Filter") {
+            None | Some(serde_json::Value::Null) => false,
+            Some(serde_json::Value::Array(filters)) => !filters.is_empty(),
+            Some(_) => true,
+        };
+
+        let decoded = if let Some(length) = qpdf_json_int(json, dict, "/DL") {
+            length
+        } else if !has_filter {
+            encoded
+        } else {
+            qpdf_json_image_size(json, dict).unwrap_or(encoded)
+        };
+
+        analysis.stream_count += 1;
+        analysis.compressed_stream_bytes += encoded as usize;
+        analysis.uncompressed_stream_bytes += decoded as usize;
+    }
+
+    Ok(analysis)
+}
+
+/// Count the indirect objects in a PDF, as seen by QPDF
+///
+/// Requires the `json-typed` feature. The trailer is not counted.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the number of objects in QPDF's JSON output.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ConversionFailed` if the PDF cannot be converted or the
+/// JSON has no object map.
+/// Returns `PdfiumError::MalformedJson` if QPDF's output does not parse.
+#[cfg(feature = "json-typed")]
+pub fn pdf_object_count(pdf_bytes: &[u8]) -> Result<usize> {
+    let json = pdf_to_json_value(pdf_bytes)?;
+
+    // v2: qpdf[1] holds "obj:N G R" keys plus "trailer"; v1: "objects" holds "N G R" keys
+    if let Some(objects) = json.get("qpdf").and_then(|q| q.get(1)).and_then(|o| o.as_object()) {
+        return Ok(objects.keys().filter(|key| key.starts_with("obj:")).count());
+    }
+    if let Some(objects) = json.get("objects").and_then(|o| o.as_object()) {
+        return Ok(objects.keys().filter(|key| *key != "trailer").count());
+    }
+
+    Err(PdfiumError::ConversionFailed(
+        "QPDF JSON has no object map".to_string()
+    ))
+}
+
+/// Cleanup PDFium library
+///
+/// This should be called at program exit. It's optional as the OS will clean up
+/// resources anyway, but it's good practice to call it explicitly. A later
+/// `initialize` (or any API call) re-runs the library configuration.
+///
+/// Raw handles returned by `pdfium_wasm_load_custom_document` are tracked
+/// along with `Document`s, so they must be closed first (with
+/// `pdfium_wasm_close_document`).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::ResourcesStillOpen` if any `Document` or raw handle
+/// from `pdfium_wasm_load_custom_document` is still open.
+/// The library is left untouched in that case.
+pub fn cleanup() -> Result<()> {
+    let mut initialized = lock_library()?;
+
+    let open = OPEN_DOCUMENTS.load(Ordering::SeqCst);
+    if open > 0 {
+        return Err(PdfiumError::ResourcesStillOpen(open));
+    }
+
+    if *initialized {
+        unsafe {
+            ffi::FPDF_DestroyLibrary();
+        }
+        *initialized = false;
+    }
+
+    Ok(())
+}
+
+/// Free a string returned by pdfium_wasm_extract_text or pdfium_wasm_pdf_to_json
+#[no_mangle]
+pub extern "C" fn pdfium_wasm_free_string(ptr: *mut u8) {
+    if !ptr.is_null() {
+        ffi_guard((), || unsafe {
+            let _ = std::ffi::CString::from_raw(ptr as *mut i8);
+        })
+    }
+}
+
+/// Cleanup PDFium library (C ABI for WASM)
+/// Returns 1 on success, 0 if documents are still open
+#[no_mangle]
+pub extern "C" fn pdfium_wasm_cleanup() -> i32 {
+    ffi_guard(0, || match cleanup() {
+        Ok(_) => 1,
+        Err(_) => 0,
+    })
+}
+
+/// The thread that created the first `SingleThreadPdfium` token
+static PDFIUM_THREAD: Mutex<Option<std::thread::ThreadId>> = Mutex::new(None);
+
+/// A token confining PDFium work to one thread
+///
+/// PDFium keeps process-global state and none of its handles are thread-safe.
+/// The free functions in this crate serialize library setup and teardown, but
+/// nothing stops two threads from calling into PDFium at once. Code that may
+/// run on several threads should instead create one token and do all PDFium
+/// work through it: the token is neither `Send` nor `Sync`, and only the first
+/// thread that creates one may create more.
+///
+/// The free functions remain usable for single-threaded hosts such as the
+/// browser, where WASM runs on one thread anyway.
+pub struct SingleThreadPdfium {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl SingleThreadPdfium {
+    /// Claim PDFium for the current thread and initialize the library
+    ///
+    /// # Errors
+    ///
+    /// Returns `PdfiumError::InitializationFailed` if a token was already
+    /// created on a different thread.
+    pub fn new() -> Result<Self> {
+        let mut owner = PDFIUM_THREAD
+            .lock()
+            .map_err(|_| PdfiumError::InitializationFailed)?;
+        let current = std::thread::current().id();
+        match *owner {
+            Some(thread) if thread != current => return Err(PdfiumError::InitializationFailed),
+            _ => *owner = Some(current),
+        }
+        drop(owner);
+
+        initialize()?;
+        Ok(SingleThreadPdfium {
+            _not_send: PhantomData,
+        })
+    }
+
+    /// Load a PDF document from memory (see `Document::load`)
+    pub fn load<'a>(&self, pdf_bytes: &'a [u8]) -> Result<Document<'a>> {
+        Document::load(pdf_bytes)
+    }
+
+    /// Load a possibly encrypted PDF document (see `Document::load_with_password`)
+    pub fn load_with_password<'a>(
+        &self,
+        pdf_bytes: &'a [u8],
+        password: Option<&str>,
+    ) -> Result<Document<'a>> {
+        Document::load_with_password(pdf_bytes, password)
+    }
+
+    /// Extract text from a PDF document (see `extract_text`)
+    pub fn extract_text(&self, pdf_bytes: &[u8]) -> Result<String> {
+        extract_text(pdf_bytes)
+    }
+}
+
+/// Return the library to its never-initialized state
+///
+/// Requires the `test-utils` feature. Initialization is already re-runnable
+/// after `cleanup`; this additionally releases the `SingleThreadPdfium`
+/// thread claim, so each test (which the test harness runs on its own
+/// thread) can start from scratch and exercise the init and cleanup paths.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::ResourcesStillOpen` if any `Document` is still alive;
+/// nothing is reset in that case.
+#[cfg(feature = "test-utils")]
+pub fn reset_for_testing() -> Result<()> {
+    cleanup()?;
+    *PDFIUM_THREAD
+        .lock()
+        .map_err(|_| PdfiumError::InitializationFailed)? = None;
+    Ok(())
+}
+
+// ============================================================================
+// Build Information
+// ============================================================================
+
+/// How this module was compiled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// Whether the `qpdf` feature (JSON conversion, linearization, encryption) is compiled in
+    pub qpdf_enabled: bool,
+    /// PDFium version, if the linked build reports one
+    ///
+    /// The bundled `libpdfium.a` does not export `FPDF_GetModuleVersion`, so
+    /// this is currently always `None`.
+    pub pdfium_version: Option<String>,
+    /// Target triple, e.g. `wasm32-unknown-emscripten`
+    pub target: &'static str,
+}
+
+/// Report how this module was compiled
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        qpdf_enabled: cfg!(feature = "qpdf"),
+        pdfium_version: None,
+        target: env!("PDFIUM_WASM_TARGET"),
+    }
+}
+
+/// Quote a string as a JSON string literal
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Report how this module was compiled, as JSON (C ABI for WASM)
+/// Returns e.g. `{"qpdf_enabled":true,"pdfium_version":null,"target":"wasm32-unknown-emscripten"}`
+/// Caller must free the returned string with pdfium_wasm_free_string
+#[no_mangle]
+pub extern "C" fn pdfium_wasm_build_info_json() -> *mut u8 {
+    ffi_guard(std::ptr::null_mut(), || {
+        let info = build_info();
+        let json = format!(
+            "{{\"qpdf_enabled\":{},\"pdfium_version\":{},\"target\":{}}}",
+            info.qpdf_enabled,
+            info.pdfium_version.as_deref().map_or("null".to_string(), json_string),
+            json_string(info.target),
+        );
+        into_c_string(json)
+    })
+}
+
+// ============================================================================
+// Document Handles
+// ============================================================================
+
+/// An open PDFium document
+///
+/// The document borrows the bytes it was loaded from (or owns the reader, for
+/// `load_from_reader`), since PDFium reads from its source lazily. The handle
+/// is closed on drop, and while any `Document` is alive `cleanup()` refuses to
+/// destroy the library.
+///
+/// `Document` is neither `Send` nor `Sync`: PDFium handles must only be used
+/// on the thread that created them (see `SingleThreadPdfium`).
+pub struct Document<'a> {
+    handle: ffi::FPDF_DOCUMENT,
+    _data: PhantomData<&'a [u8]>,
+    // Reader state PDFium's callback points into; dropped after the handle is closed
+    _source: Option<Box<dyn BlockSource + 'a>>,
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Largest input accepted by `Document::load` and `pdf_to_json` (0 for no limit)
+static MAX_DOCUMENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Cap the size of in-memory documents the library will open
+///
+/// Applies to every function that loads a document from a byte slice through
+/// `Document::load` (text extraction, rendering, ...) and to the QPDF ones
+/// (JSON conversion, validation, rewriting), which reject larger inputs with
+/// `DocumentTooLarge` before PDFium or QPDF sees them. 0, the default, means
+/// no limit. Documents opened with `Document::load_from_reader` are bounded by
+/// their `ReaderLimits` instead.
+pub fn set_max_document_bytes(limit: usize) {
+    MAX_DOCUMENT_BYTES.store(limit, Ordering::SeqCst);
+}
+
+/// Return `DocumentTooLarge` if `size` exceeds the `set_max_document_bytes` limit
+fn check_document_size(size: usize) -> Result<()> {
+    let limit = MAX_DOCUMENT_BYTES.load(Ordering::SeqCst);
+    if limit != 0 && size > limit {
+        return Err(PdfiumError::DocumentTooLarge { size, limit });
+    }
+    Ok(())
+}
+
+/// Caps on how much a `Document::load_from_reader` source may be read
+///
+/// PDFium keeps reading from the source while pages are loaded, so the caps
+/// apply for the document's whole lifetime, not only while opening it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReaderLimits {
+    /// Maximum number of block reads PDFium may request (`None` for no limit)
+    pub max_reads: Option<u64>,
+    /// Maximum total bytes PDFium may request (`None` for no limit)
+    pub max_bytes: Option<u64>,
+}
+
+/// Type-erased owner of a reader that PDFium's block callback points into
+trait BlockSource {}
+
+/// State shared with `get_block_trampoline` for a reader-backed document
+struct ReaderSource<R> {
+    reader: R,
+    limits: ReaderLimits,
+    reads: u64,
+    bytes: u64,
+    exceeded: bool,
+    error: Option<std::io::Error>,
+}
+
+impl<R> BlockSource for ReaderSource<R> {}
+
+/// Read callback for `IPDF_StreamingIO_LoadDocument`; `user_data` is a `ReaderSource<R>`
+unsafe extern "C" fn get_block_trampoline<R: std::io::Read + std::io::Seek>(
+    user_data: *mut std::os::raw::c_void,
+    position: std::os::raw::c_ulong,
+    buffer: *mut std::os::raw::c_uchar,
+    size: std::os::raw::c_ulong,
+) -> std::os::raw::c_int {
+    let source = &mut *(user_data as *mut ReaderSource<R>);
+    if source.exceeded || source.error.is_some() {
+        return 0;
+    }
+    if size == 0 {
+        return 1;
+    }
+    if buffer.is_null() {
+        return 0;
+    }
+
+    source.reads += 1;
+    source.bytes = source.bytes.saturating_add(u64::from(size));
+    let over_reads = source.limits.max_reads.is_some_and(|max| source.reads > max);
+    let over_bytes = source.limits.max_bytes.is_some_and(|max| source.bytes > max);
+    if over_reads || over_bytes {
+        source.exceeded = true;
+        return 0;
+    }
+
+    let chunk = std::slice::from_raw_parts_mut(buffer, size as usize);
+
+    // A panicking reader must not unwind into PDFium
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        source.reader.seek(std::io::SeekFrom::Start(u64::from(position)))?;
+        source.reader.read_exact(chunk)
+    }));
+    match result {
+        Ok(Ok(())) => 1,
+        Ok(Err(e)) => {
+            source.error = Some(e);
+            0
+        }
+        Err(_) => {
+            source.error = Some(std::io::Error::other("reader panicked"));
+            0
+        }
+    }
+}
+
+impl<'a> Document<'a> {
+    /// Load a PDF document from memory, initializing PDFium if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns `PdfiumError::InvalidData` if the input is empty or too large.
+    /// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+    /// Returns `PdfiumError::PasswordRequired` if the document is encrypted.
+    /// Returns `PdfiumError::ExtractionFailed` if PDFium cannot load the document.
+    pub fn load(pdf_bytes: &'a [u8]) -> Result<Self> {
+        Self::load_with_password(pdf_bytes, None)
+    }
+
+    /// Load a possibly encrypted PDF document from memory
+    ///
+    /// # Errors
+    ///
+    /// Returns `PdfiumError::InvalidData` if the input is empty or too large,
+    /// or if the password contains a NUL byte.
+    /// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+    /// Returns `PdfiumError::PasswordRequired` if the password is missing or wrong.
+    /// Returns `PdfiumError::ExtractionFailed` if PDFium cannot load the document.
+    pub fn load_with_password(pdf_bytes: &'a [u8], password: Option<&str>) -> Result<Self> {
+        if pdf_bytes.is_empty() {
+            return Err(PdfiumError::InvalidData);
+        }
+        check_document_size(pdf_bytes.len())?;
+        let size = i32::try_from(pdf_bytes.len()).map_err(|_| PdfiumError::InvalidData)?;
+        let password = password
+            .map(std::ffi::CString::new)
+            .transpose()
+            .map_err(|_| PdfiumError::InvalidData)?;
+
+        // Hold the lifecycle lock until the handle is counted
+        let mut initialized = lock_library()?;
+        init_locked(&mut initialized);
+
+        let handle = unsafe {
+            ffi::FPDF_LoadMemDocument(
+                pdf_bytes.as_ptr() as *const std::ffi::c_void,
+                size,
+                password.as_ref().map_or(std::ptr::null(), |p| p.as_ptr()),
+            )
+        };
+
+        if handle.is_null() {
+            if unsafe { ffi::FPDF_GetLastError() } == ffi::FPDF_ERR_PASSWORD {
+                return Err(PdfiumError::PasswordRequired);
+            }
+            return Err(PdfiumError::ExtractionFailed(
+                "Failed to load PDF document".to_string()
+            ));
+        }
+
+        Ok(Self::from_handle(handle))
+    }
+
+    /// Wrap a freshly obtained handle and count it as open
+    fn from_handle(handle: ffi::FPDF_DOCUMENT) -> Self {
+        OPEN_DOCUMENTS.fetch_add(1, Ordering::SeqCst);
+        Document {
+            handle,
+            _data: PhantomData,
+            _source: None,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Load a PDF document from a seekable reader, fetching blocks on demand
+    ///
+    /// Only the parts PDFium needs are read, so large files need not be held in
+    /// memory. `limits` bounds the reads, which protects against sources that
+    /// would otherwise make PDFium keep requesting data; once a limit is hit
+    /// every later read fails, so pages that are not loaded yet fail to load.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PdfiumError::InvalidData` if the source is empty or too large
+    /// for PDFium's 32-bit offsets.
+    /// Returns `PdfiumError::Io` if the reader fails while the document is opened.
+    /// Returns `PdfiumError::StreamLimitExceeded` if `limits` are exceeded while
+    /// the document is opened.
+    /// Returns `PdfiumError::PasswordRequired` if the document is encrypted.
+    /// Returns `PdfiumError::ExtractionFailed` if PDFium cannot load the document.
+    pub fn load_from_reader<R>(mut reader: R, limits: ReaderLimits) -> Result<Self>
+    where
+        R: std::io::Read + std::io::Seek + 'a,
+    {
+        let length = reader
+            .seek(std::io::SeekFrom::End(0))
+            .map_err(|e| PdfiumError::Io(e.to_string()))?;
+        if length == 0 {
+            return Err(PdfiumError::InvalidData);
+        }
+        let file_size =
+            std::os::raw::c_ulong::try_from(length).map_err(|_| PdfiumError::InvalidData)?;
+
+        let mut source = Box::new(ReaderSource {
+            reader,
+            limits,
+            reads: 0,
+            bytes: 0,
+            exceeded: false,
+            error: None,
+        });
+
+        // Hold the lifecycle lock until the handle is counted
+        let mut initialized = lock_library()?;
+        init_locked(&mut initialized);
+
+        let handle = unsafe {
+            ffi::IPDF_StreamingIO_LoadDocument(
+                file_size,
+                Some(get_block_trampoline::<R>),
+                &mut *source as *mut ReaderSource<R> as *mut std::os::raw::c_void,
+                std::ptr::null(),
+            )
+        };
+
+        if handle.is_null() {
+            if source.exceeded {
+                return Err(PdfiumError::StreamLimitExceeded);
+            }
+            if let Some(e) = source.error.take() {
+                return Err(PdfiumError::Io(e.to_string()));
+            }
+            if unsafe { ffi::FPDF_GetLastError() } == ffi::FPDF_ERR_PASSWORD {
+                return Err(PdfiumError::PasswordRequired);
+            }
+            return Err(PdfiumError::ExtractionFailed(
+                "Failed to load PDF document".to_string()
+            ));
+        }
+
+        let mut doc = Self::from_handle(handle);
+        doc._source = Some(source);
+        Ok(doc)
+    }
+
+    /// Take ownership of a raw `FPDF_DOCUMENT` handle
+    ///
+    /// This is how handles from the streaming loader (`pdfium_wasm_load_custom_document`)
+    /// get a safe wrapper. The handle is closed when the `Document` is dropped,
+    /// so it must not also be passed to `pdfium_wasm_close_document`.
+    /// A null handle is accepted; methods on it report an error.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be null or an open handle returned by
+    /// `pdfium_wasm_load_custom_document` and not owned elsewhere, and whatever
+    /// data its loader reads from (the callback's `user_data`) must stay valid
+    /// for `'a`.
+    pub unsafe fn from_raw(handle: *mut std::os::raw::c_void) -> Self {
+        // The loader already counted the handle as open; adopt that count
+        Document {
+            handle,
+            _data: PhantomData,
+            _source: None,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Number of pages, queried through the streaming I/O helper
+    ///
+    /// Intended for documents obtained via `from_raw` from the streaming loader,
+    /// but works for any document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PdfiumError::ExtractionFailed` if the handle is null or PDFium
+    /// reports an error.
+    pub fn stream_page_count(&self) -> Result<usize> {
+        if self.handle.is_null() {
+            return Err(PdfiumError::ExtractionFailed(
+                "Document handle is null".to_string()
+            ));
+        }
+
+        let count = unsafe { ffi::IPDF_StreamingIO_GetPageCount(self.handle) };
+        usize::try_from(count).map_err(|_| {
+            PdfiumError::ExtractionFailed("Failed to read page count".to_string())
+        })
+    }
+
+    /// Close the document now rather than at the end of its scope
+    ///
+    /// Equivalent to dropping it; spelled out for callers porting code from the
+    /// C API, where every load is paired with an explicit close.
+    pub fn close(self) {
+        drop(self);
+    }
+
+    /// Save the document as an incremental update to its original bytes
+    ///
+    /// The original file is written out unchanged and any modifications are
+    /// appended after it as a new revision, so the output starts with the exact
+    /// bytes the document was loaded from. Use this for signed documents, where
+    /// a full rewrite would invalidate the existing signatures' byte ranges.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Destination for the saved bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns `PdfiumError::SaveFailed` if PDFium cannot serialize the document
+    /// or the writer fails.
+    pub fn save_incremental<W: std::io::Write>(&self, mut writer: W) -> Result<()> {
+        save_document(self, &mut writer, ffi::FPDF_INCREMENTAL)
+    }
+
+    /// Number of pages in the document
+    pub fn page_count(&self) -> usize {
+        unsafe { ffi::FPDF_GetPageCount(self.handle).max(0) as usize }
+    }
+
+    fn handle(&self) -> ffi::FPDF_DOCUMENT {
+        self.handle
+    }
+
+    /// Return `PageOutOfRange` unless `index` names an existing page
+    fn check_page_index(&self, index: usize) -> Result<()> {
+        let page_count = self.page_count();
+        if index >= page_count {
+            return Err(PdfiumError::PageOutOfRange { index, page_count });
+        }
+        Ok(())
+    }
+
+    /// Load a page, or `None` if PDFium can't load it
+    fn try_page(&self, index: usize) -> Option<Page<'_>> {
+        let index = i32::try_from(index).ok()?;
+        let handle = unsafe { ffi::FPDF_LoadPage(self.handle, index) };
+        if handle.is_null() {
+            None
+        } else {
+            Some(Page {
+                handle,
+                _doc: PhantomData,
+            })
+        }
+    }
+
+    /// Load a page, reporting out-of-range indices and load failures as errors
+    fn page(&self, index: usize) -> Result<Page<'_>> {
+        self.check_page_index(index)?;
+        self.try_page(index).ok_or_else(|| {
+            PdfiumError::ExtractionFailed(format!("Failed to load page {}", index))
+        })
+    }
+}
+
+impl Document<'static> {
+    /// Create an empty document (not backed by any caller buffer)
+    fn new_empty() -> Result<Self> {
+        let mut initialized = lock_library()?;
+        init_locked(&mut initialized);
+
+        let handle = unsafe { ffi::FPDF_CreateNewDocument() };
+        if handle.is_null() {
+            return Err(PdfiumError::SaveFailed(
+                "Failed to create PDF document".to_string()
+            ));
+        }
+
+        Ok(Self::from_handle(handle))
+    }
+}
+
+impl Drop for Document<'_> {
+    fn drop(&mut self) {
+        // Only non-null handles are counted (a null one can come from `from_raw`)
+        if !self.handle.is_null() {
+            unsafe {
+                ffi::FPDF_CloseDocument(self.handle);
+            }
+            OPEN_DOCUMENTS.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Estimate a PDF's page count by scanning the raw bytes for page objects
+///
+/// Counts `/Type /Page` entries (with any whitespace, or none, between the two
+/// names) whose name ends there, which excludes `/Pages` tree nodes. This is a
+/// heuristic: pages stored in compressed object streams are missed, and stale
+/// objects left behind by incremental updates are counted. Use it to
+/// cross-check `Document::page_count` on damaged files, not as a replacement.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the approximate number of page objects.
+pub fn page_count_scan(pdf_bytes: &[u8]) -> usize {
+    const TYPE: &[u8] = b"/Type";
+    const PAGE: &[u8] = b"/Page";
+    let is_whitespace = |b: u8| matches!(b, b' ' | b'\t' | b'\r' | b'\n' | b'\x0C' | b'\0');
+    let is_delimiter = |b: u8| is_whitespace(b) || b"()<>[]{}/%".contains(&b);
+
+    let mut count = 0;
+    let mut pos = 0;
+    while let Some(offset) = pdf_bytes[pos..].windows(TYPE.len()).position(|w| w == TYPE) {
+        pos += offset + TYPE.len();
+
+        let mut value = pos;
+        while value < pdf_bytes.len() && is_whitespace(pdf_bytes[value]) {
+            value += 1;
+        }
+        if !pdf_bytes[value..].starts_with(PAGE) {
+            continue;
+        }
+        match pdf_bytes.get(value + PAGE.len()) {
+            Some(&next) if !is_delimiter(next) => {}
+            _ => count += 1,
+        }
+    }
+    count
+}
+
+/// A loaded page, closed on drop (must not outlive its document)
+struct Page<'d> {
+    handle: ffi::FPDF_PAGE,
+    _doc: PhantomData<&'d ()>,
+}
+
+impl Page<'_> {
+    fn handle(&self) -> ffi::FPDF_PAGE {
+        self.handle
+    }
+
+    /// Load the page's text layer, or `None` if PDFium can't build one
+    fn text(&self) -> Option<TextPage<'_>> {
+        let handle = unsafe { ffi::FPDFText_LoadPage(self.handle) };
+        if handle.is_null() {
+            None
+        } else {
+            Some(TextPage {
+                handle,
+                _page: PhantomData,
+            })
+        }
+    }
+
+    fn annotation_count(&self) -> usize {
+        unsafe { ffi::FPDFPage_GetAnnotCount(self.handle).max(0) as usize }
+    }
+
+    /// Open an annotation by index, or `None` if PDFium can't
+    fn annotation(&self, index: usize) -> Option<PageAnnotation<'_>> {
+        let index = i32::try_from(index).ok()?;
+        let handle = unsafe { ffi::FPDFPage_GetAnnot(self.handle, index) };
+        if handle.is_null() {
+            None
+        } else {
+            Some(PageAnnotation {
+                handle,
+                _page: PhantomData,
+            })
+        }
+    }
+}
+
+impl Drop for Page<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::FPDF_ClosePage(self.handle);
+        }
+    }
+}
+
+/// Upper bound on the UTF-16 code units read from one page (32 MiB of buffer)
+///
+/// Real pages hold a few thousand characters; a count beyond this is a
+/// corrupt or hostile document.
+const MAX_PAGE_TEXT_UNITS: usize = 16 * 1024 * 1024;
+
+/// Capacity, in UTF-16 units, the shared staging buffer keeps between pages
+///
+/// One unusually large page shouldn't pin its buffer for the thread's lifetime.
+const TEXT_STAGING_RETAIN_UNITS: usize = 64 * 1024;
+
+thread_local! {
+    /// UTF-16 buffer reused by `TextPage::append_text` across pages and documents
+    static TEXT_STAGING: std::cell::RefCell<Vec<u16>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// A page's text layer, closed on drop (must not outlive its page)
+struct TextPage<'p> {
+    handle: ffi::FPDF_TEXTPAGE,
+    _page: PhantomData<&'p ()>,
+}
+
+impl TextPage<'_> {
+    fn handle(&self) -> ffi::FPDF_TEXTPAGE {
+        self.handle
+    }
+
+    /// Number of characters PDFium recognized on the page
+    fn char_count(&self) -> usize {
+        unsafe { ffi::FPDFText_CountChars(self.handle).max(0) as usize }
+    }
+
+    /// Decode the character at `index`, returning it and the UTF-16 units it spans
+    ///
+    /// PDFium indexes UTF-16 units, so characters outside the BMP occupy two
+    /// indices; `count` bounds the lookahead for the second half. Unpaired
+    /// surrogates decode to U+FFFD.
+    fn char_at(&self, index: i32, count: i32) -> (char, i32) {
+        unsafe {
+            let unit = ffi::FPDFText_GetUnicode(self.handle, index);
+            if (0xD800..=0xDBFF).contains(&unit) && index + 1 < count {
+                let low = ffi::FPDFText_GetUnicode(self.handle, index + 1);
+                if (0xDC00..=0xDFFF).contains(&low) {
+                    let ch = char::from_u32(0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00));
+                    return (ch.unwrap_or(char::REPLACEMENT_CHARACTER), 2);
+                }
+            }
+            (char::from_u32(unit).unwrap_or(char::REPLACEMENT_CHARACTER), 1)
+        }
+    }
+
+    /// The page's full text, decoded from PDFium's UTF-16 output
+    ///
+    /// # Errors
+    ///
+    /// Returns `PdfiumError::ExtractionFailed` if the page reports more than
+    /// `MAX_PAGE_TEXT_UNITS` characters.
+    fn text(&self) -> Result<String> {
+        let mut text = String::new();
+        self.append_text(&mut text)?;
+        Ok(text)
+    }
+
+    /// Append the page's text to `out`, staging it in the thread's shared buffer
+    ///
+    /// # Errors
+    ///
+    /// Same as `text`; `out` is left untouched on error.
+    fn append_text(&self, out: &mut String) -> Result<()> {
+        TEXT_STAGING.with(|staging| {
+            let mut buffer = staging.borrow_mut();
+            let result = self.append_text_with(&mut buffer, out);
+
+            buffer.clear();
+            if buffer.capacity() > TEXT_STAGING_RETAIN_UNITS {
+                buffer.shrink_to(TEXT_STAGING_RETAIN_UNITS);
+            }
+            result
+        })
+    }
+
+    fn append_text_with(&self, buffer: &mut Vec<u16>, out: &mut String) -> Result<()> {
+        unsafe {
+            // Negative counts signal an error; treat them like an empty page
+            let text_length = ffi::FPDFText_CountChars(self.handle);
+            let length = usize::try_from(text_length).unwrap_or(0);
+            if length == 0 {
+                return Ok(());
+            }
+            if length > MAX_PAGE_TEXT_UNITS {
+                return Err(PdfiumError::ExtractionFailed(format!(
+                    "Page reports {} characters, more than the {} supported",
+                    length, MAX_PAGE_TEXT_UNITS
+                )));
+            }
+
+            // Size the buffer for the UTF-16 text plus the terminator
+            buffer.clear();
+            buffer.resize(length + 1, 0);
+            let chars_written =
+                ffi::FPDFText_GetText(self.handle, 0, text_length, buffer.as_mut_ptr());
+
+            // The count includes the terminator; never trust it past the buffer
+            let written = usize::try_from(chars_written).unwrap_or(0).min(buffer.len());
+            if written == 0 {
+                return Ok(());
+            }
+
+            trim_text_buffer(buffer, written);
+
+            // Decode straight into the output, without an intermediate String
+            out.reserve(buffer.len());
+            out.extend(
+                char::decode_utf16(buffer.iter().copied())
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)),
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Cut a `FPDFText_GetText` buffer down to the text it actually holds
+///
+/// `written` counts the terminator, which is dropped along with everything
+/// after it. A short write can split a surrogate pair; the dangling high half
+/// is dropped too, rather than letting the decoder turn it into U+FFFD.
+fn trim_text_buffer(buffer: &mut Vec<u16>, written: usize) {
+    buffer.truncate(written.saturating_sub(1));
+    if buffer.last().is_some_and(|&unit| (0xD800..=0xDBFF).contains(&unit)) {
+        buffer.pop();
+    }
+}
+
+impl Drop for TextPage<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::FPDFText_ClosePage(self.handle);
+        }
+    }
+}
+
+/// An owned PDFium bitmap, destroyed on drop
+struct Bitmap {
+    handle: ffi::FPDF_BITMAP,
+}
+
+impl Bitmap {
+    /// Take ownership of a bitmap handle, or `None` if it is null
+    fn from_handle(handle: ffi::FPDF_BITMAP) -> Option<Self> {
+        if handle.is_null() {
+            None
+        } else {
+            Some(Bitmap { handle })
+        }
+    }
+
+    fn width(&self) -> u32 {
+        unsafe { ffi::FPDFBitmap_GetWidth(self.handle).max(0) as u32 }
+    }
+
+    fn height(&self) -> u32 {
+        unsafe { ffi::FPDFBitmap_GetHeight(self.handle).max(0) as u32 }
+    }
+
+    /// Copy the pixels out as tightly packed BGRA, whatever the source format
+    ///
+    /// Returns `None` for formats PDFium doesn't document.
+    fn to_bgra(&self) -> Option<Vec<u8>> {
+        unsafe {
+            let width = self.width() as usize;
+            let height = self.height() as usize;
+            let stride = ffi::FPDFBitmap_GetStride(self.handle).max(0) as usize;
+            let buffer = ffi::FPDFBitmap_GetBuffer(self.handle) as *const u8;
+            if buffer.is_null() {
+                return None;
+            }
+
+            let format = ffi::FPDFBitmap_GetFormat(self.handle);
+            let bytes_per_pixel = match format {
+                ffi::FPDFBITMAP_GRAY => 1,
+                ffi::FPDFBITMAP_BGR => 3,
+                ffi::FPDFBITMAP_BGRX | ffi::FPDFBITMAP_BGRA => 4,
+                _ => return None,
+            };
+            if width == 0 || height == 0 || stride < width * bytes_per_pixel {
+                return None;
+            }
+
+            let data = std::slice::from_raw_parts(buffer, stride * height);
+            let mut pixels = Vec::with_capacity(width * height * 4);
+            for row in data.chunks_exact(stride) {
+                for px in row[..width * bytes_per_pixel].chunks_exact(bytes_per_pixel) {
+                    match format {
+                        ffi::FPDFBITMAP_GRAY => pixels.extend_from_slice(&[px[0], px[0], px[0], 0xFF]),
+                        // BGRx carries no alpha; treat the padding byte as opaque
+                        ffi::FPDFBITMAP_BGR | ffi::FPDFBITMAP_BGRX => {
+                            pixels.extend_from_slice(&[px[0], px[1], px[2], 0xFF])
+                        }
+                        _ => pixels.extend_from_slice(px),
+                    }
+                }
+            }
+
+            Some(pixels)
+        }
+    }
+}
+
+impl Drop for Bitmap {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::FPDFBitmap_Destroy(self.handle);
+        }
+    }
+}
+
+/// A page annotation, closed on drop (must not outlive its page)
+struct PageAnnotation<'p> {
+    handle: ffi::FPDF_ANNOTATION,
+    _page: PhantomData<&'p ()>,
+}
+
+impl Drop for PageAnnotation<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::FPDFPage_CloseAnnot(self.handle);
+        }
+    }
+}
+
+/// A form-fill environment, torn down on drop (must not outlive its document)
+struct FormEnvironment<'d> {
+    handle: ffi::FPDF_FORMHANDLE,
+    // PDFium keeps a pointer to this for the environment's whole lifetime
+    _info: Box<ffi::FPDF_FORMFILLINFO>,
+    _doc: PhantomData<&'d ()>,
+}
+
+impl<'d> FormEnvironment<'d> {
+    /// Set up a callback-free environment, or `None` if PDFium refuses
+    fn new(doc: &'d Document) -> Option<Self> {
+        let mut info = Box::new(ffi::FPDF_FORMFILLINFO {
+            version: 1,
+            callbacks: [std::ptr::null_mut(); 15],
+            m_pJsPlatform: std::ptr::null_mut(),
+        });
+        let handle = unsafe { ffi::FPDFDOC_InitFormFillEnvironment(doc.handle(), &mut *info) };
+        if handle.is_null() {
+            None
+        } else {
+            Some(FormEnvironment {
+                handle,
+                _info: info,
+                _doc: PhantomData,
+            })
+        }
+    }
+}
+
+impl Drop for FormEnvironment<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::FPDFDOC_ExitFormFillEnvironment(self.handle);
+        }
+    }
+}
+
+// ============================================================================
+// Text Cache
+// ============================================================================
+
+/// Capacity used until `set_text_cache_capacity` is called
+#[cfg(feature = "cache")]
+const DEFAULT_TEXT_CACHE_CAPACITY: usize = 32;
+
+/// Maximum number of documents kept by `extract_text_cached` (0 disables it)
+#[cfg(feature = "cache")]
+static TEXT_CACHE_CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_TEXT_CACHE_CAPACITY);
+
+/// Extracted text keyed by the XXH3-128 hash of the input bytes, created on first use
+#[cfg(feature = "cache")]
+static TEXT_CACHE: Mutex<Option<lru::LruCache<u128, String>>> = Mutex::new(None);
+
+/// Lock the text cache; it only holds finished strings, so a poisoned lock is still usable
+#[cfg(feature = "cache")]
+fn lock_text_cache() -> MutexGuard<'static, Option<lru::LruCache<u128, String>>> {
+    TEXT_CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Extract text from a PDF document, reusing earlier results for identical input
+///
+/// Requires the `cache` feature. Results are cached in memory by a 128-bit
+/// XXH3 hash of `pdf_bytes`, least recently used entries being evicted first.
+/// The cache lock is not held while extracting, so concurrent misses on the
+/// same document may both extract it.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the same text as `extract_text`. Errors are not cached.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+#[cfg(feature = "cache")]
+pub fn extract_text_cached(pdf_bytes: &[u8]) -> Result<String> {
+    // Reject before hashing, so over-limit input is neither hashed nor served from the cache
+    if pdf_bytes.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+    check_document_size(pdf_bytes.len())?;
+
+    let capacity = TEXT_CACHE_CAPACITY.load(Ordering::SeqCst);
+    let Some(capacity) = std::num::NonZeroUsize::new(capacity) else {
+        return extract_text(pdf_bytes);
+    };
+
+    let key = xxhash_rust::xxh3::xxh3_128(pdf_bytes);
+    let cached = lock_text_cache()
+        .get_or_insert_with(|| lru::LruCache::new(capacity))
+        .get(&key)
+        .cloned();
+    if let Some(text) = cached {
+        return Ok(text);
+    }
+
+    let text = extract_text(pdf_bytes)?;
+    if let Some(cache) = lock_text_cache().as_mut() {
+        cache.put(key, text.clone());
+    }
+    Ok(text)
+}
+
+/// Set how many documents `extract_text_cached` keeps (default 32)
+///
+/// Requires the `cache` feature. Shrinking evicts the least recently used
+/// entries; 0 empties the cache and disables it until a non-zero capacity is set.
+#[cfg(feature = "cache")]
+pub fn set_text_cache_capacity(capacity: usize) {
+    let mut cache = lock_text_cache();
+    TEXT_CACHE_CAPACITY.store(capacity, Ordering::SeqCst);
+    match std::num::NonZeroUsize::new(capacity) {
+        Some(capacity) => {
+            if let Some(cache) = cache.as_mut() {
+                cache.resize(capacity);
+            }
+        }
+        None => *cache = None,
+    }
+}
+
+// ============================================================================
+// Render Cache
+// ============================================================================
+
+/// Byte budget used until `set_render_cache_capacity` is called (32 MiB)
+#[cfg(feature = "render-cache")]
+const DEFAULT_RENDER_CACHE_CAPACITY: usize = 32 << 20;
+
+/// Maximum total pixel bytes kept by `render_page_cached` (0 disables it)
+#[cfg(feature = "render-cache")]
+static RENDER_CACHE_CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_RENDER_CACHE_CAPACITY);
+
+/// `(input hash, page index, width, height)`
+#[cfg(feature = "render-cache")]
+type RenderKey = (u128, usize, u32, u32);
+
+/// Renders in least recently used order, with their combined size
+#[cfg(feature = "render-cache")]
+struct RenderCache {
+    entries: lru::LruCache<RenderKey, Vec<u8>>,
+    bytes: usize,
+}
+
+#[cfg(feature = "render-cache")]
+impl RenderCache {
+    /// Evict least recently used renders until at most `capacity` bytes remain
+    fn shrink_to(&mut self, capacity: usize) {
+        while self.bytes > capacity {
+            match self.entries.pop_lru() {
+                Some((_, pixels)) => self.bytes -= pixels.len(),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Page renders, created on first use
+#[cfg(feature = "render-cache")]
+static RENDER_CACHE: Mutex<Option<RenderCache>> = Mutex::new(None);
+
+/// Lock the render cache; it only holds finished buffers, so a poisoned lock is still usable
+#[cfg(feature = "render-cache")]
+fn lock_render_cache() -> MutexGuard<'static, Option<RenderCache>> {
+    RENDER_CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Render a page, reusing an earlier render of the same page at the same size
+///
+/// Requires the `render-cache` feature. Renders are cached in memory by a
+/// 128-bit XXH3 hash of `pdf_bytes` together with the page index and size, and
+/// the least recently used renders are evicted once their total size exceeds
+/// the byte budget. A render larger than the whole budget is returned but not
+/// cached. As with `extract_text_cached`, the lock is not held while rendering.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `width` - Output width in pixels
+/// * `height` - Output height in pixels
+///
+/// # Returns
+///
+/// Returns the same pixels as `render_page`. Errors are not cached.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty or a dimension is zero.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::RenderFailed` if PDFium cannot render the page.
+#[cfg(feature = "render-cache")]
+pub fn render_page_cached(
+    pdf_bytes: &[u8],
+    page_index: usize,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>> {
+    // Reject before hashing, so over-limit input is neither hashed nor served from the cache
+    if pdf_bytes.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+    check_document_size(pdf_bytes.len())?;
+
+    let capacity = RENDER_CACHE_CAPACITY.load(Ordering::SeqCst);
+    if capacity == 0 {
+        return render_page(pdf_bytes, page_index, width, height);
+    }
+
+    let key = (xxhash_rust::xxh3::xxh3_128(pdf_bytes), page_index, width, height);
+    if let Some(pixels) = lock_render_cache()
+        .as_mut()
+        .and_then(|cache| cache.entries.get(&key).cloned())
+    {
+        return Ok(pixels);
+    }
+
+    let pixels = render_page(pdf_bytes, page_index, width, height)?;
+
+    // Re-read the budget: it may have changed while rendering
+    let mut cache = lock_render_cache();
+    let capacity = RENDER_CACHE_CAPACITY.load(Ordering::SeqCst);
+    if pixels.len() <= capacity {
+        let cache = cache.get_or_insert_with(|| RenderCache {
+            entries: lru::LruCache::unbounded(),
+            bytes: 0,
+        });
+        cache.bytes += pixels.len();
+        if let Some(replaced) = cache.entries.put(key, pixels.clone()) {
+            cache.bytes -= replaced.len();
+        }
+        cache.shrink_to(capacity);
+    }
+    Ok(pixels)
+}
+
+/// Set the total size in bytes of the renders `render_page_cached` keeps (default 32 MiB)
+///
+/// Requires the `render-cache` feature. The budget counts pixel bytes, not
+/// entries, since renders vary widely in size. Shrinking evicts the least
+/// recently used renders; 0 empties the cache and disables it until a non-zero
+/// budget is set.
+#[cfg(feature = "render-cache")]
+pub fn set_render_cache_capacity(bytes: usize) {
+    let mut cache = lock_render_cache();
+    RENDER_CACHE_CAPACITY.store(bytes, Ordering::SeqCst);
+    if bytes == 0 {
+        *cache = None;
+    } else if let Some(cache) = cache.as_mut() {
+        cache.shrink_to(bytes);
+    }
+}
+
+// ============================================================================
+// Document Metadata
+// ============================================================================
+
+/// Document information dictionary entries
+///
+/// Entries that are missing from the document (or present but empty) are `None`.
+/// Dates are returned verbatim in PDF date syntax (e.g. `D:20240131120000+01'00'`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub creation_date: Option<String>,
+    pub mod_date: Option<String>,
+}
+
+/// Decode a UTF-16LE byte buffer as returned by PDFium, dropping the NUL terminator
+fn utf16le_to_string(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Read a UTF-16 string through one of PDFium's two-call getters
+///
+/// `get(buffer, length)` is first called with a null buffer to learn the size
+/// in bytes (including the UTF-16 terminator), then again to fill the buffer.
+/// Returns an empty string if the value is absent or empty.
+unsafe fn read_utf16_bytes(
+    mut get: impl FnMut(*mut std::ffi::c_void, std::os::raw::c_ulong) -> std::os::raw::c_ulong,
+) -> String {
+    let needed = get(std::ptr::null_mut(), 0);
+    if needed <= 2 {
+        return String::new();
+    }
+
+    let mut buffer: Vec<u8> = vec![0; needed as usize];
+    let written = get(buffer.as_mut_ptr() as *mut std::ffi::c_void, needed);
+    buffer.truncate(written.min(needed) as usize);
+    utf16le_to_string(&buffer)
+}
+
+/// Read a single metadata entry, returning `None` when absent or empty
+unsafe fn read_meta_text(doc: &Document, tag: &std::ffi::CStr) -> Option<String> {
+    let doc = doc.handle();
+    let value = read_utf16_bytes(|buffer, length| {
+        ffi::FPDF_GetMetaText(doc, tag.as_ptr(), buffer, length)
+    });
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// Read the document information dictionary of a PDF
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns a `Metadata` struct with one field per standard Info entry.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+pub fn document_metadata(pdf_bytes: &[u8]) -> Result<Metadata> {
+    let doc = Document::load(pdf_bytes)?;
+
+    unsafe {
+        Ok(Metadata {
+            title: read_meta_text(&doc, c"Title"),
+            author: read_meta_text(&doc, c"Author"),
+            subject: read_meta_text(&doc, c"Subject"),
+            keywords: read_meta_text(&doc, c"Keywords"),
+            creator: read_meta_text(&doc, c"Creator"),
+            producer: read_meta_text(&doc, c"Producer"),
+            creation_date: read_meta_text(&doc, c"CreationDate"),
+            mod_date: read_meta_text(&doc, c"ModDate"),
+        })
+    }
+}
+
+/// Creation and modification times of a document
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DocumentDates {
+    pub created: Option<chrono::DateTime<chrono::Utc>>,
+    pub modified: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Parse a PDF date string (`D:YYYYMMDDHHmmSSOHH'mm'`) into UTC
+///
+/// Everything after the year is optional, as is the `D:` prefix and the
+/// apostrophes in the offset. A missing offset is taken as UTC. Trailing
+/// characters that are not part of the offset make the date invalid.
+#[cfg(feature = "chrono")]
+fn parse_pdf_date(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::TimeZone;
+
+    let value = value.trim();
+    let value = value.strip_prefix("D:").unwrap_or(value);
+    let digits = value.bytes().take_while(u8::is_ascii_digit).count();
+    if !(4..=14).contains(&digits) || digits % 2 != 0 {
+        return None;
+    }
+
+    // Two-digit field starting at `start`, or `default` if the string ends first
+    let field = |start: usize, default: u32| -> Option<u32> {
+        if start < digits {
+            value[start..start + 2].parse().ok()
+        } else {
+            Some(default)
+        }
+    };
+    let year: i32 = value[..4].parse().ok()?;
+    let month = field(4, 1)?;
+    let day = field(6, 1)?;
+    let hour = field(8, 0)?;
+    let minute = field(10, 0)?;
+    let second = field(12, 0)?;
+
+    // Some producers write `Z00'00'`, so UTC may carry a (zero) offset too
+    let zone = &value[digits..];
+    let offset_seconds = match zone.as_bytes().first() {
+        None => 0,
+        Some(b'Z') if zone.len() == 1 => 0,
+        Some(b'Z') => match parse_pdf_offset(&zone[1..])? {
+            0 => 0,
+            _ => return None,
+        },
+        Some(b'+') => parse_pdf_offset(&zone[1..])?,
+        Some(b'-') => -parse_pdf_offset(&zone[1..])?,
+        Some(_) => return None,
+    };
+
+    let offset = chrono::FixedOffset::east_opt(offset_seconds)?;
+    let local =
+        chrono::NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, second)?;
+    offset
+        .from_local_datetime(&local)
+        .single()
+        .map(|date| date.with_timezone(&chrono::Utc))
+}
+
+/// Parse the `HH'mm'` after a PDF date's offset sign into seconds
+///
+/// The minutes and both apostrophes are optional; anything else is rejected.
+#[cfg(feature = "chrono")]
+fn parse_pdf_offset(zone: &str) -> Option<i32> {
+    let two_digits = |s: &str| -> Option<i32> {
+        let field = s.get(..2)?;
+        if !field.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        field.parse().ok()
+    };
+
+    let hours = two_digits(zone)?;
+    let rest = &zone[2..];
+    let rest = rest.strip_prefix('\'').unwrap_or(rest);
+    let minutes = if rest.is_empty() {
+        0
+    } else {
+        let minutes = two_digits(rest)?;
+        if !matches!(&rest[2..], "" | "'") {
+            return None;
+        }
+        minutes
+    };
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(hours * 3600 + minutes * 60)
+}
+
+/// Read the creation and modification dates of a PDF as timestamps
+///
+/// Requires the `chrono` feature. Dates that are missing or don't follow the
+/// PDF date syntax are `None`.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the `CreationDate` and `ModDate` Info entries converted to UTC.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+#[cfg(feature = "chrono")]
+pub fn document_dates(pdf_bytes: &[u8]) -> Result<DocumentDates> {
+    let doc = Document::load(pdf_bytes)?;
+
+    unsafe {
+        Ok(DocumentDates {
+            created: read_meta_text(&doc, c"CreationDate").and_then(|d| parse_pdf_date(&d)),
+            modified: read_meta_text(&doc, c"ModDate").and_then(|d| parse_pdf_date(&d)),
+        })
+    }
+}
+
+/// Get the PDF version declared in the file header
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns `(major, minor)`, e.g. `(1, 7)` for a `%PDF-1.7` file.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded or has no
+/// readable version.
+pub fn pdf_version(pdf_bytes: &[u8]) -> Result<(u8, u8)> {
+    let doc = Document::load(pdf_bytes)?;
+
+    // PDFium encodes the version as major * 10 + minor (17 for 1.7)
+    let mut version: std::os::raw::c_int = 0;
+    let ok = unsafe { ffi::FPDF_GetFileVersion(doc.handle(), &mut version) };
+    if ok == 0 || !(10..=99).contains(&version) {
+        return Err(PdfiumError::ExtractionFailed(
+            "Failed to read PDF version".to_string()
+        ));
+    }
+
+    Ok(((version / 10) as u8, (version % 10) as u8))
+}
+
+/// Get the printed label of a page (e.g. `iv` or `A-3`) from the `/PageLabels` tree
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the label, or `None` if the document defines no label for the page.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+pub fn page_label(pdf_bytes: &[u8], page_index: usize) -> Result<Option<String>> {
+    let doc = Document::load(pdf_bytes)?;
+    doc.check_page_index(page_index)?;
+    let index = page_index as std::os::raw::c_int;
+
+    let label = unsafe {
+        read_utf16_bytes(|buffer, length| {
+            ffi::FPDF_GetPageLabel(doc.handle(), index, buffer, length)
+        })
+    };
+    Ok(if label.is_empty() { None } else { Some(label) })
+}
+
+// ============================================================================
+// Document Outline
+// ============================================================================
+
+/// Maximum outline nesting followed by `outline`
+const MAX_OUTLINE_DEPTH: usize = 32;
+
+/// One entry of the document outline (bookmarks)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineItem {
+    pub title: String,
+    /// Zero-based target page, or `None` if the entry doesn't point into the document
+    pub page_index: Option<usize>,
+    pub children: Vec<OutlineItem>,
+}
+
+/// Read a bookmark's title (empty if it has none)
+unsafe fn bookmark_title(bookmark: ffi::FPDF_BOOKMARK) -> String {
+    read_utf16_bytes(|buffer, length| ffi::FPDFBookmark_GetTitle(bookmark, buffer, length))
+}
+
+/// Resolve a bookmark's target page from its `/Dest`, or a GoTo action's destination
+unsafe fn bookmark_page(doc: &Document, bookmark: ffi::FPDF_BOOKMARK) -> Option<usize> {
+    let mut dest = ffi::FPDFBookmark_GetDest(doc.handle(), bookmark);
+    if dest.is_null() {
+        let action = ffi::FPDFBookmark_GetAction(bookmark);
+        if !action.is_null() {
+            dest = ffi::FPDFAction_GetDest(doc.handle(), action);
+        }
+    }
+    if dest.is_null() {
+        return None;
+    }
+
+    usize::try_from(ffi::FPDFDest_GetDestPageIndex(doc.handle(), dest)).ok()
+}
+
+/// Collect the children of `parent` (null for the top level)
+///
+/// `visited` holds every bookmark seen so far, so `/First` or `/Next` links that
+/// point back into the tree are cut instead of looping forever.
+unsafe fn outline_children(
+    doc: &Document,
+    parent: ffi::FPDF_BOOKMARK,
+    depth: usize,
+    visited: &mut std::collections::HashSet<usize>,
+) -> Vec<OutlineItem> {
+    let mut items = Vec::new();
+    if depth >= MAX_OUTLINE_DEPTH {
+        return items;
+    }
+
+    let mut bookmark = ffi::FPDFBookmark_GetFirstChild(doc.handle(), parent);
+    while !bookmark.is_null() && visited.insert(bookmark as usize) {
+        items.push(OutlineItem {
+            title: bookmark_title(bookmark),
+            page_index: bookmark_page(doc, bookmark),
+            children: outline_children(doc, bookmark, depth + 1, visited),
+        });
+        bookmark = ffi::FPDFBookmark_GetNextSibling(doc.handle(), bookmark);
+    }
+
+    items
+}
+
+/// Read the document outline (bookmarks) as a tree
+///
+/// Nesting deeper than 32 levels is dropped, as are entries that would revisit
+/// an earlier one in a malformed, cyclic outline.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the top-level entries in document order (empty if there is no outline).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+pub fn outline(pdf_bytes: &[u8]) -> Result<Vec<OutlineItem>> {
+    let doc = Document::load(pdf_bytes)?;
+    let mut visited = std::collections::HashSet::new();
+    Ok(unsafe { outline_children(&doc, std::ptr::null_mut(), 0, &mut visited) })
+}
+
+// ============================================================================
+// Links
+// ============================================================================
+
+/// A link annotation on a page
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    /// Target of a URI action
+    pub uri: Option<String>,
+    /// Zero-based target page of a GoTo action or direct destination
+    pub target_page: Option<usize>,
+    /// Clickable area as `(left, bottom, right, top)` in page points
+    pub rect: (f64, f64, f64, f64),
+}
+
+/// Read the URI of a `PDFACTION_URI` action
+unsafe fn action_uri(doc: &Document, action: ffi::FPDF_ACTION) -> Option<String> {
+    // The URI is 7-bit ASCII; the size includes the NUL terminator
+    let needed = ffi::FPDFAction_GetURIPath(doc.handle(), action, std::ptr::null_mut(), 0);
+    if needed <= 1 {
+        return None;
+    }
+
+    let mut buffer: Vec<u8> = vec![0; needed as usize];
+    let written = ffi::FPDFAction_GetURIPath(
+        doc.handle(),
+        action,
+        buffer.as_mut_ptr() as *mut std::ffi::c_void,
+        needed,
+    );
+    buffer.truncate(written.min(needed) as usize);
+    if let Some(end) = buffer.iter().position(|&b| b == 0) {
+        buffer.truncate(end);
+    }
+
+    Some(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+/// List the links on a page
+///
+/// URI actions fill `uri`; GoTo actions and plain destinations fill `target_page`.
+/// Other actions (launch, remote GoTo, JavaScript, ...) leave both `None`.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the page's links in annotation order (empty if it has none).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or page cannot be loaded.
+pub fn extract_links(pdf_bytes: &[u8], page_index: usize) -> Result<Vec<Link>> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+    let mut links = Vec::new();
+
+    unsafe {
+        let mut pos: std::os::raw::c_int = 0;
+        let mut link: ffi::FPDF_LINK = std::ptr::null_mut();
+        while ffi::FPDFLink_Enumerate(page.handle(), &mut pos, &mut link) != 0 {
+            let mut rect = ffi::FS_RECTF::default();
+            ffi::FPDFLink_GetAnnotRect(link, &mut rect);
+
+            let mut uri = None;
+            let mut dest = ffi::FPDFLink_GetDest(doc.handle(), link);
+            let action = ffi::FPDFLink_GetAction(link);
+            if !action.is_null() {
+                match ffi::FPDFAction_GetType(action) {
+                    ffi::PDFACTION_URI => uri = action_uri(&doc, action),
+                    ffi::PDFACTION_GOTO if dest.is_null() => {
+                        dest = ffi::FPDFAction_GetDest(doc.handle(), action)
+                    }
+                    _ => {}
+                }
+            }
+            let target_page = if dest.is_null() {
+                None
+            } else {
+                usize::try_from(ffi::FPDFDest_GetDestPageIndex(doc.handle(), dest)).ok()
+            };
+
+            links.push(Link {
+                uri,
+                target_page,
+                rect: (
+                    rect.left as f64,
+                    rect.bottom as f64,
+                    rect.right as f64,
+                    rect.top as f64,
+                ),
+            });
+        }
+    }
+
+    Ok(links)
+}
+
+/// List the document's named destinations and the pages they point to
+///
+/// Covers both the `/Dests` dictionary and the `/Dests` name tree, which is
+/// what `#nameddest=` links refer to.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns `(name, page_index)` pairs with zero-based page indices, in
+/// PDFium's enumeration order. Destinations whose target is not a page of the
+/// document are skipped.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+pub fn named_destinations(pdf_bytes: &[u8]) -> Result<Vec<(String, usize)>> {
+    let doc = Document::load(pdf_bytes)?;
+    let page_count = doc.page_count();
+    let count = unsafe { ffi::FPDF_CountNamedDests(doc.handle()) };
+    let count = i32::try_from(count).unwrap_or(i32::MAX);
+    let mut destinations = Vec::new();
+
+    unsafe {
+        for index in 0..count {
+            // First call reports the name's size in bytes (UTF-16LE, with terminator)
+            let mut length: std::os::raw::c_long = 0;
+            ffi::FPDF_GetNamedDest(doc.handle(), index, std::ptr::null_mut(), &mut length);
+            if length <= 2 {
+                continue;
+            }
+
+            let mut buffer: Vec<u8> = vec![0; length as usize];
+            let dest = ffi::FPDF_GetNamedDest(
+                doc.handle(),
+                index,
+                buffer.as_mut_ptr() as *mut std::ffi::c_void,
+                &mut length,
+            );
+            // A negative length means the buffer was too small
+            if dest.is_null() || length <= 0 {
+                continue;
+            }
+            buffer.truncate(length as usize);
+
+            let page_index = ffi::FPDFDest_GetDestPageIndex(doc.handle(), dest);
+            match usize::try_from(page_index) {
+                Ok(page_index) if page_index < page_count => {
+                    destinations.push((utf16le_to_string(&buffer), page_index))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(destinations)
+}
+
+// ============================================================================
+// Form Fields
+// ============================================================================
+
+/// A form field widget and its current value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormField {
+    /// Fully qualified field name (e.g. `address.city`)
+    pub name: String,
+    pub value: String,
+    pub page_index: usize,
+    /// One of `text`, `checkbox`, `radiobutton`, `combobox`, `listbox`,
+    /// `pushbutton`, `signature` or `unknown`
+    pub field_type: String,
+}
+
+/// Read a UTF-16 string through one of PDFium's two-call form field getters
+unsafe fn read_form_string(
+    getter: unsafe extern "C" fn(
+        ffi::FPDF_FORMHANDLE,
+        ffi::FPDF_ANNOTATION,
+        *mut u16,
+        std::os::raw::c_ulong,
+    ) -> std::os::raw::c_ulong,
+    form: &FormEnvironment,
+    annot: &PageAnnotation,
+) -> String {
+    read_utf16_bytes(|buffer, length| getter(form.handle, annot.handle, buffer as *mut u16, length))
+}
+
+fn form_field_type_name(field_type: std::os::raw::c_int) -> &'static str {
+    match field_type {
+        ffi::FPDF_FORMFIELD_TEXTFIELD => "text",
+        ffi::FPDF_FORMFIELD_CHECKBOX => "checkbox",
+        ffi::FPDF_FORMFIELD_RADIOBUTTON => "radiobutton",
+        ffi::FPDF_FORMFIELD_COMBOBOX => "combobox",
+        ffi::FPDF_FORMFIELD_LISTBOX => "listbox",
+        ffi::FPDF_FORMFIELD_PUSHBUTTON => "pushbutton",
+        ffi::FPDF_FORMFIELD_SIGNATURE => "signature",
+        _ => "unknown",
+    }
+}
+
+/// Read the AcroForm fields of a document
+///
+/// Fields are reported per widget, so a radio group with three buttons yields
+/// three entries sharing a name.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the fields in page and annotation order (empty if there is no form).
+/// Pages that fail to load are skipped.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded or the
+/// form-fill environment cannot be created.
+pub fn read_form_fields(pdf_bytes: &[u8]) -> Result<Vec<FormField>> {
+    let doc = Document::load(pdf_bytes)?;
+    let form = FormEnvironment::new(&doc).ok_or_else(|| {
+        PdfiumError::ExtractionFailed("Failed to initialize form-fill environment".to_string())
+    })?;
+    let mut fields = Vec::new();
+
+    for page_index in 0..doc.page_count() {
+        let Some(page) = doc.try_page(page_index) else {
+            continue;
+        };
+
+        for i in 0..page.annotation_count() {
+            let Some(annot) = page.annotation(i) else {
+                continue;
+            };
+
+            unsafe {
+                // -1 for annotations that aren't form widgets
+                let field_type = ffi::FPDFAnnot_GetFormFieldType(form.handle, annot.handle);
+                if field_type < 0 {
+                    continue;
+                }
+
+                fields.push(FormField {
+                    name: read_form_string(ffi::FPDFAnnot_GetFormFieldName, &form, &annot),
+                    value: read_form_string(ffi::FPDFAnnot_GetFormFieldValue, &form, &annot),
+                    page_index,
+                    field_type: form_field_type_name(field_type).to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(fields)
+}
+
+// ============================================================================
+// Annotations
+// ============================================================================
+
+/// Count the annotations on every page of a PDF
+///
+/// Only `FPDFPage_GetAnnotCount` is called per page; no annotation is opened.
+/// Form widgets and links are annotations too and are included in the counts.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns one count per page, in page order. Pages without annotations and
+/// pages that fail to load report 0.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+pub fn annotation_counts(pdf_bytes: &[u8]) -> Result<Vec<usize>> {
+    let doc = Document::load(pdf_bytes)?;
+
+    Ok((0..doc.page_count())
+        .map(|page_index| doc.try_page(page_index).map_or(0, |page| page.annotation_count()))
+        .collect())
+}
+
+/// A comment or markup annotation with its text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// PDF subtype name (e.g. `Text`, `Highlight`, `FreeText`)
+    pub subtype: String,
+    /// The annotation's `/Contents` text
+    pub contents: String,
+    /// Annotation area as `(left, bottom, right, top)` in page points
+    pub rect: (f64, f64, f64, f64),
+    /// The `/T` entry, which markup annotations use for the author
+    pub author: Option<String>,
+}
+
+/// PDF name of an `FPDFAnnot_GetSubtype` value
+fn annotation_subtype_name(subtype: std::os::raw::c_int) -> &'static str {
+    const NAMES: [&str; 29] = [
+        "Unknown",
+        "Text",
+        "Link",
+        "FreeText",
+        "Line",
+        "Square",
+        "Circle",
+        "Polygon",
+        "PolyLine",
+        "Highlight",
+        "Underline",
+        "Squiggly",
+        "StrikeOut",
+        "Stamp",
+        "Caret",
+        "Ink",
+        "Popup",
+        "FileAttachment",
+        "Sound",
+        "Movie",
+        "Widget",
+        "Screen",
+        "PrinterMark",
+        "TrapNet",
+        "Watermark",
+        "3D",
+        "RichMedia",
+        "XFAWidget",
+        "Redact",
+    ];
+    usize::try_from(subtype)
+        .ok()
+        .and_then(|i| NAMES.get(i))
+        .copied()
+        .unwrap_or("Unknown")
+}
+
+/// Read a string entry of an annotation dictionary
+unsafe fn annot_string_value(annot: &PageAnnotation, key: &std::ffi::CStr) -> String {
+    let key = key.as_ptr();
+    read_utf16_bytes(|buffer, length| {
+        ffi::FPDFAnnot_GetStringValue(annot.handle, key, buffer as *mut u16, length)
+    })
+}
+
+/// Read the comments and markup on a page
+///
+/// Links, form widgets and popups are skipped, as is any annotation with empty
+/// `/Contents` (a highlight nobody commented on, for example).
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the annotations in page order (empty if there are none).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or page cannot be loaded.
+pub fn extract_annotations(pdf_bytes: &[u8], page_index: usize) -> Result<Vec<Annotation>> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+    let mut annotations = Vec::new();
+
+    for i in 0..page.annotation_count() {
+        let Some(annot) = page.annotation(i) else {
+            continue;
+        };
+
+        unsafe {
+            let subtype = ffi::FPDFAnnot_GetSubtype(annot.handle);
+            if matches!(
+                subtype,
+                ffi::FPDF_ANNOT_LINK | ffi::FPDF_ANNOT_POPUP | ffi::FPDF_ANNOT_WIDGET
+            ) {
+                continue;
+            }
+
+            let contents = annot_string_value(&annot, c"Contents");
+            if contents.is_empty() {
+                continue;
+            }
+
+            let author = annot_string_value(&annot, c"T");
+            let mut rect = ffi::FS_RECTF::default();
+            ffi::FPDFAnnot_GetRect(annot.handle, &mut rect);
+
+            annotations.push(Annotation {
+                subtype: annotation_subtype_name(subtype).to_string(),
+                contents,
+                rect: (
+                    rect.left as f64,
+                    rect.bottom as f64,
+                    rect.right as f64,
+                    rect.top as f64,
+                ),
+                author: (!author.is_empty()).then_some(author),
+            });
+        }
+    }
+
+    Ok(annotations)
+}
+
+/// Flatten a page's annotation appearances into its content and extract the text
+///
+/// With `keep_page_content` false the page's own objects are removed first,
+/// leaving only the text drawn by annotations. The document is only modified
+/// in memory.
+fn flattened_page_text(
+    pdf_bytes: &[u8],
+    page_index: usize,
+    keep_page_content: bool,
+) -> Result<String> {
+    let doc = Document::load(pdf_bytes)?;
+
+    {
+        let page = doc.page(page_index)?;
+        unsafe {
+            if !keep_page_content {
+                for i in (0..ffi::FPDFPage_CountObjects(page.handle())).rev() {
+                    let object = ffi::FPDFPage_GetObject(page.handle(), i);
+                    // Once removed, the object belongs to us and must be freed
+                    if !object.is_null() && ffi::FPDFPage_RemoveObject(page.handle(), object) != 0 {
+                        ffi::FPDFPageObj_Destroy(object);
+                    }
+                }
+                if ffi::FPDFPage_GenerateContent(page.handle()) == 0 {
+                    return Err(PdfiumError::ExtractionFailed(format!(
+                        "Failed to regenerate content of page {}",
+                        page_index
+                    )));
+                }
+            }
+
+            if ffi::FPDFPage_Flatten(page.handle(), ffi::FLAT_NORMALDISPLAY) == ffi::FLATTEN_FAIL {
+                return Err(PdfiumError::ExtractionFailed(format!(
+                    "Failed to flatten annotations of page {}",
+                    page_index
+                )));
+            }
+        }
+    }
+
+    // Flattening rewrites the page dictionary; only a freshly loaded page sees it
+    let page = doc.page(page_index)?;
+    let text_page = page.text().ok_or_else(|| {
+        PdfiumError::ExtractionFailed(format!("Failed to load text of page {}", page_index))
+    })?;
+    text_page.text()
+}
+
+/// Extract the text drawn by a page's annotations
+///
+/// Stamps, free-text comments and form field values are drawn from the
+/// annotations' normal appearance streams, which `extract_text` never looks
+/// at. This flattens those appearances (as `flatten_pdf` does, in memory)
+/// onto an emptied copy of the page and extracts what they render. Hidden
+/// annotations and annotations without an appearance stream contribute nothing.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the annotation text only, without the page's own text; use
+/// `extract_text_with_annotations` for both.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or page cannot be loaded
+/// or its annotations cannot be flattened.
+pub fn extract_annotation_text(pdf_bytes: &[u8], page_index: usize) -> Result<String> {
+    flattened_page_text(pdf_bytes, page_index, false)
+}
+
+/// Extract a page's text together with the text drawn by its annotations
+///
+/// The annotation appearances are flattened into the page (in memory) before
+/// extraction, so their text appears where it is drawn, interleaved with the
+/// page text in PDFium's usual reading order.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the combined text of the page and its annotations.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or page cannot be loaded
+/// or its annotations cannot be flattened.
+pub fn extract_text_with_annotations(pdf_bytes: &[u8], page_index: usize) -> Result<String> {
+    flattened_page_text(pdf_bytes, page_index, true)
+}
+
+// ============================================================================
+// Text Search
+// ============================================================================
+
+/// A single match returned by `search_text`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchHit {
+    /// Zero-based page index
+    pub page_index: usize,
+    /// Index of the first matched character within the page's text
+    pub char_index: usize,
+    /// Number of characters covered by the match
+    pub char_count: usize,
+}
+
+/// Find all occurrences of a string in a PDF document
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `needle` - The text to search for
+/// * `match_case` - Only match occurrences with the same case
+/// * `whole_word` - Only match occurrences that form a whole word
+///
+/// # Returns
+///
+/// Returns every match in page order. An empty vector means no matches.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input or the needle is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+pub fn search_text(
+    pdf_bytes: &[u8],
+    needle: &str,
+    match_case: bool,
+    whole_word: bool,
+) -> Result<Vec<SearchHit>> {
+    if needle.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+
+    let mut flags = 0;
+    if match_case {
+        flags |= ffi::FPDF_MATCHCASE;
+    }
+    if whole_word {
+        flags |= ffi::FPDF_MATCHWHOLEWORD;
+    }
+
+    // PDFium expects a NUL-terminated UTF-16 string
+    let mut pattern: Vec<u16> = needle.encode_utf16().collect();
+    pattern.push(0);
+
+    let doc = Document::load(pdf_bytes)?;
+    let mut hits = Vec::new();
+
+    for i in 0..doc.page_count() {
+        let Some(page) = doc.try_page(i) else {
+            continue;
+        };
+        let Some(text_page) = page.text() else {
+            continue;
+        };
+
+        unsafe {
+            let search = ffi::FPDFText_FindStart(text_page.handle(), pattern.as_ptr(), flags, 0);
+            if search.is_null() {
+                continue;
+            }
+
+            while ffi::FPDFText_FindNext(search) != 0 {
+                hits.push(SearchHit {
+                    page_index: i,
+                    char_index: ffi::FPDFText_GetSchResultIndex(search).max(0) as usize,
+                    char_count: ffi::FPDFText_GetSchCount(search).max(0) as usize,
+                });
+            }
+            ffi::FPDFText_FindClose(search);
+        }
+    }
+
+    Ok(hits)
+}
+
+// ============================================================================
+// Page Geometry and Rendering
+// ============================================================================
+
+/// Look up a page's size in points on an already loaded document
+fn page_size_in(doc: &Document, page_index: usize) -> Result<(f64, f64)> {
+    doc.check_page_index(page_index)?;
+
+    let mut width = 0.0;
+    let mut height = 0.0;
+    let ok = unsafe {
+        ffi::IPDF_StreamingIO_GetPageSize(doc.handle(), page_index as i32, &mut width, &mut height)
+    };
+    if ok == 0 {
+        return Err(PdfiumError::ExtractionFailed(format!(
+            "Failed to read size of page {}",
+            page_index
+        )));
+    }
+
+    Ok((width, height))
+}
+
+/// Render a page on an already loaded document
+fn render_page_in(doc: &Document, page_index: usize, width: u32, height: u32) -> Result<Vec<u8>> {
+    doc.check_page_index(page_index)?;
+
+    let (w, h) = match (i32::try_from(width), i32::try_from(height)) {
+        (Ok(w), Ok(h)) if w > 0 && h > 0 => (w, h),
+        _ => return Err(PdfiumError::InvalidData),
+    };
+
+    unsafe {
+        let mut out_size: std::os::raw::c_ulong = 0;
+        let pixels_ptr =
+            ffi::IPDF_StreamingIO_RenderPage(doc.handle(), page_index as i32, w, h, &mut out_size);
+        if pixels_ptr.is_null() {
+            return Err(PdfiumError::RenderFailed(format!(
+                "Failed to render page {}",
+                page_index
+            )));
+        }
+
+        // Copy into Rust-owned memory, then release the C buffer
+        let pixels = std::slice::from_raw_parts(pixels_ptr, out_size as usize).to_vec();
+        ffi::IPDF_StreamingIO_FreeString(pixels_ptr as *mut std::ffi::c_void);
+
+        Ok(pixels)
+    }
+}
+
+/// Get the size of a page in PDF points (1/72 inch)
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns `(width, height)` in points.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+pub fn page_size(pdf_bytes: &[u8], page_index: usize) -> Result<(f64, f64)> {
+    let doc = Document::load(pdf_bytes)?;
+    page_size_in(&doc, page_index)
+}
+
+/// Get the size of a page as a viewer displays it, in PDF points
+///
+/// Computed from the page's visible box (the crop box clipped to the media
+/// box) with width and height swapped when `/Rotate` is 90 or 270 degrees, so
+/// the aspect ratio matches what a viewer shows.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns `(width, height)` in points after rotation.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or page cannot be loaded.
+pub fn page_display_size(pdf_bytes: &[u8], page_index: usize) -> Result<(f64, f64)> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+
+    let mut rect = ffi::FS_RECTF::default();
+    let (ok, quarter_turns) = unsafe {
+        (
+            ffi::FPDF_GetPageBoundingBox(page.handle(), &mut rect),
+            ffi::FPDFPage_GetRotation(page.handle()),
+        )
+    };
+    if ok == 0 {
+        return Err(PdfiumError::ExtractionFailed(format!(
+            "Failed to read size of page {}",
+            page_index
+        )));
+    }
+
+    let width = (rect.right - rect.left).abs() as f64;
+    let height = (rect.top - rect.bottom).abs() as f64;
+    if quarter_turns % 2 == 1 {
+        Ok((height, width))
+    } else {
+        Ok((width, height))
+    }
+}
+
+/// Get the `/Rotate` of every page as quarter turns
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns one entry per page: the clockwise rotation in quarter turns
+/// (`0..=3`, i.e. 0, 90, 180 or 270 degrees). Pages that fail to load are
+/// reported as 0, so one broken page doesn't cost the others their rotation.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+pub fn page_rotations(pdf_bytes: &[u8]) -> Result<Vec<u8>> {
+    let doc = Document::load(pdf_bytes)?;
+    Ok((0..doc.page_count())
+        .map(|index| match doc.try_page(index) {
+            Some(page) => {
+                let quarter_turns = unsafe { ffi::FPDFPage_GetRotation(page.handle()) };
+                quarter_turns.rem_euclid(4) as u8
+            }
+            None => 0,
+        })
+        .collect())
+}
+
+/// The media and crop boxes of a page
+///
+/// Boxes are `(left, bottom, right, top)` in page points, as written in the
+/// page dictionary (or inherited from the page tree), before `/Rotate`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageBoxes {
+    pub media: (f64, f64, f64, f64),
+    /// `None` when the page has no crop box, in which case it defaults to `media`
+    pub crop: Option<(f64, f64, f64, f64)>,
+}
+
+/// Read one of a page's boxes through a `FPDFPage_Get*Box` getter
+unsafe fn read_page_box(
+    page: &Page,
+    getter: unsafe extern "C" fn(
+        ffi::FPDF_PAGE,
+        *mut f32,
+        *mut f32,
+        *mut f32,
+        *mut f32,
+    ) -> std::os::raw::c_int,
+) -> Option<(f64, f64, f64, f64)> {
+    let (mut left, mut bottom, mut right, mut top) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    if getter(page.handle(), &mut left, &mut bottom, &mut right, &mut top) == 0 {
+        return None;
+    }
+    Some((left as f64, bottom as f64, right as f64, top as f64))
+}
+
+/// Read the media box and crop box of a page
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the page's `PageBoxes`. A page without a media box (invalid, but
+/// seen in the wild) reports US Letter, `(0, 0, 612, 792)`, as PDFium assumes.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or page cannot be loaded.
+pub fn page_boxes(pdf_bytes: &[u8], page_index: usize) -> Result<PageBoxes> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+
+    unsafe {
+        Ok(PageBoxes {
+            media: read_page_box(&page, ffi::FPDFPage_GetMediaBox)
+                .unwrap_or((0.0, 0.0, 612.0, 792.0)),
+            crop: read_page_box(&page, ffi::FPDFPage_GetCropBox),
+        })
+    }
+}
+
+/// Render a page to a pixel buffer
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `width` - Output width in pixels
+/// * `height` - Output height in pixels
+///
+/// # Returns
+///
+/// Returns the page as RGBA pixels (4 bytes per pixel, rows top to bottom),
+/// rendered over a white background by the streaming helper.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty or a dimension is zero.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::RenderFailed` if PDFium cannot render the page.
+pub fn render_page(pdf_bytes: &[u8], page_index: usize, width: u32, height: u32) -> Result<Vec<u8>> {
+    let doc = Document::load(pdf_bytes)?;
+    render_page_in(&doc, page_index, width, height)
+}
+
+/// Convert a length in points to pixels at the given DPI, never returning 0
+fn points_to_pixels(points: f64, dpi: f32) -> u32 {
+    let pixels = (points / 72.0 * dpi as f64).round();
+    pixels.clamp(1.0, i32::MAX as f64) as u32
+}
+
+/// Render a page at a given resolution
+///
+/// The pixel size is derived from the page size: `points / 72 * dpi`, rounded.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `dpi` - Output resolution in dots per inch (e.g. 72 for screen, 300 for print)
+///
+/// # Returns
+///
+/// Returns `(pixels, width, height)` where `pixels` has the same layout as `render_page`.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty or `dpi` is not positive.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::RenderFailed` if PDFium cannot render the page.
+pub fn render_page_at_dpi(pdf_bytes: &[u8], page_index: usize, dpi: f32) -> Result<(Vec<u8>, u32, u32)> {
+    // Written this way so NaN is rejected too
+    if !(dpi > 0.0 && dpi.is_finite()) {
+        return Err(PdfiumError::InvalidData);
+    }
+
+    // Reuse the loaded document for both the size lookup and the render
+    let doc = Document::load(pdf_bytes)?;
+    let (width_pt, height_pt) = page_size_in(&doc, page_index)?;
+    let width = points_to_pixels(width_pt, dpi);
+    let height = points_to_pixels(height_pt, dpi);
+
+    let pixels = render_page_in(&doc, page_index, width, height)?;
+    Ok((pixels, width, height))
+}
+
+/// Options for `render_page_with_options`
+///
+/// Built with the `with_*` methods starting from `RenderOptions::new()`:
+///
+/// ```ignore
+/// let options = RenderOptions::new().with_grayscale(true).with_background(0xFFFF_FFFF);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    annotations: bool,
+    grayscale: bool,
+    lcd_text: bool,
+    background: u32,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            annotations: true,
+            grayscale: false,
+            lcd_text: false,
+            background: 0xFFFF_FFFF,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Annotations on, color output, no LCD text, opaque white background
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw annotations such as form widgets and comments (`FPDF_ANNOT`)
+    pub fn with_annotations(mut self, enabled: bool) -> Self {
+        self.annotations = enabled;
+        self
+    }
+
+    /// Render in grayscale (`FPDF_GRAYSCALE`)
+    pub fn with_grayscale(mut self, enabled: bool) -> Self {
+        self.grayscale = enabled;
+        self
+    }
+
+    /// Background color as `0xAARRGGBB`, filled before the page is drawn
+    pub fn with_background(mut self, argb: u32) -> Self {
+        self.background = argb;
+        self
+    }
+
+    /// Use LCD-optimized text antialiasing (`FPDF_LCD_TEXT`)
+    pub fn with_lcd_text(mut self, enabled: bool) -> Self {
+        self.lcd_text = enabled;
+        self
+    }
+
+    fn flags(&self) -> i32 {
+        let mut flags = 0;
+        if self.annotations {
+            flags |= ffi::FPDF_ANNOT;
+        }
+        if self.grayscale {
+            flags |= ffi::FPDF_GRAYSCALE;
+        }
+        if self.lcd_text {
+            flags |= ffi::FPDF_LCD_TEXT;
+        }
+        flags
+    }
+}
+
+/// Render a page into a new PDFium bitmap
+fn render_bitmap(
+    doc: &Document,
+    page_index: usize,
+    width: u32,
+    height: u32,
+    options: &RenderOptions,
+) -> Result<Bitmap> {
+    let (w, h) = match (i32::try_from(width), i32::try_from(height)) {
+        (Ok(w), Ok(h)) if w > 0 && h > 0 => (w, h),
+        _ => return Err(PdfiumError::InvalidData),
+    };
+
+    let page = doc.page(page_index)?;
+
+    unsafe {
+        // Alpha channel so the background color's alpha is honored
+        let bitmap = Bitmap::from_handle(ffi::FPDFBitmap_Create(w, h, 1)).ok_or_else(|| {
+            PdfiumError::RenderFailed(format!("Failed to allocate {}x{} bitmap", width, height))
+        })?;
+
+        let background = options.background as std::os::raw::c_ulong;
+        ffi::FPDFBitmap_FillRect(bitmap.handle, 0, 0, w, h, background);
+        ffi::FPDF_RenderPageBitmap(bitmap.handle, page.handle(), 0, 0, w, h, 0, options.flags());
+
+        Ok(bitmap)
+    }
+}
+
+/// Render a page with explicit PDFium render options
+///
+/// Unlike `render_page`, this drives PDFium's bitmap API directly rather than
+/// the streaming helper, so all `RenderOptions` take effect.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `width` - Output width in pixels
+/// * `height` - Output height in pixels
+/// * `options` - Render flags and background color
+///
+/// # Returns
+///
+/// Returns the page as tightly packed BGRA pixels (4 bytes per pixel, rows top to bottom).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty or a dimension is zero.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::RenderFailed` if the bitmap cannot be allocated or read.
+pub fn render_page_with_options(
+    pdf_bytes: &[u8],
+    page_index: usize,
+    width: u32,
+    height: u32,
+    options: RenderOptions,
+) -> Result<Vec<u8>> {
+    let doc = Document::load(pdf_bytes)?;
+    let bitmap = render_bitmap(&doc, page_index, width, height, &options)?;
+    bitmap
+        .to_bgra()
+        .ok_or_else(|| PdfiumError::RenderFailed("Unsupported bitmap format".to_string()))
+}
+
+/// Render a page over a fully transparent background
+///
+/// Areas the page doesn't paint keep alpha 0, so the result can be composited
+/// over other content. Equivalent to `render_page_with_options` with a
+/// background of `0x0000_0000`.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `width` - Output width in pixels
+/// * `height` - Output height in pixels
+///
+/// # Returns
+///
+/// Returns tightly packed BGRA pixels (4 bytes per pixel, rows top to bottom)
+/// with straight, not premultiplied, alpha.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty or a dimension is zero.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::RenderFailed` if the bitmap cannot be allocated or read.
+pub fn render_page_transparent(
+    pdf_bytes: &[u8],
+    page_index: usize,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>> {
+    let options = RenderOptions::new().with_background(0x0000_0000);
+    render_page_with_options(pdf_bytes, page_index, width, height, options)
+}
+
+/// Swap the blue and red channels of every 4-byte pixel, in place
+///
+/// Turns the BGRA output of `render_page_with_options` and friends into the
+/// RGBA layout most image encoders and canvas APIs expect (and back again).
+///
+/// # Panics
+///
+/// Panics if `buf.len()` is not a multiple of 4.
+pub fn bgra_to_rgba(buf: &mut [u8]) {
+    assert!(buf.len().is_multiple_of(4), "pixel buffer length must be a multiple of 4");
+    for px in buf.chunks_exact_mut(4) {
+        px.swap(0, 2);
+    }
+}
+
+/// Byte layout of a pixel in a `RenderedPage`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 4 bytes: blue, green, red, alpha
+    Bgra,
+    /// 4 bytes: blue, green, red, unused
+    Bgrx,
+    /// 3 bytes: blue, green, red
+    Bgr,
+    /// 1 byte of luminance
+    Gray,
+}
+
+impl PixelFormat {
+    /// Map a `FPDFBitmap_GetFormat` value, or `None` for unknown formats
+    fn from_bitmap_format(format: i32) -> Option<Self> {
+        match format {
+            ffi::FPDFBITMAP_BGRA => Some(PixelFormat::Bgra),
+            ffi::FPDFBITMAP_BGRX => Some(PixelFormat::Bgrx),
+            ffi::FPDFBITMAP_BGR => Some(PixelFormat::Bgr),
+            ffi::FPDFBITMAP_GRAY => Some(PixelFormat::Gray),
+            _ => None,
+        }
+    }
+
+    /// Size of one pixel in bytes
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Bgra | PixelFormat::Bgrx => 4,
+            PixelFormat::Bgr => 3,
+            PixelFormat::Gray => 1,
+        }
+    }
+}
+
+/// A rendered page bitmap, copied verbatim from PDFium including row padding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedPage {
+    pub width: u32,
+    pub height: u32,
+    /// Bytes per row, at least `width * format.bytes_per_pixel()`
+    pub stride: u32,
+    pub format: PixelFormat,
+    /// `stride * height` bytes, rows top to bottom
+    pub pixels: Vec<u8>,
+}
+
+/// Render a page and return the bitmap with its exact memory layout
+///
+/// Unlike the other render functions, which repack pixels into tightly packed
+/// rows, this hands back PDFium's buffer as is along with its stride and pixel
+/// format, so callers never have to assume either. Rendered with the default
+/// `RenderOptions`.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `width` - Output width in pixels
+/// * `height` - Output height in pixels
+///
+/// # Returns
+///
+/// Returns a `RenderedPage` describing the bitmap.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty or a dimension is zero.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::RenderFailed` if the bitmap cannot be allocated or read.
+pub fn render_page_full(
+    pdf_bytes: &[u8],
+    page_index: usize,
+    width: u32,
+    height: u32,
+) -> Result<RenderedPage> {
+    let doc = Document::load(pdf_bytes)?;
+    let bitmap = render_bitmap(&doc, page_index, width, height, &RenderOptions::new())?;
+
+    unsafe {
+        let format = PixelFormat::from_bitmap_format(ffi::FPDFBitmap_GetFormat(bitmap.handle))
+            .ok_or_else(|| PdfiumError::RenderFailed("Unsupported bitmap format".to_string()))?;
+        let stride = ffi::FPDFBitmap_GetStride(bitmap.handle).max(0) as u32;
+        let buffer = ffi::FPDFBitmap_GetBuffer(bitmap.handle) as *const u8;
+        let row_bytes = bitmap.width() as usize * format.bytes_per_pixel();
+        if buffer.is_null() || (stride as usize) < row_bytes {
+            return Err(PdfiumError::RenderFailed("Bitmap buffer is unavailable".to_string()));
+        }
+
+        let len = stride as usize * bitmap.height() as usize;
+        Ok(RenderedPage {
+            width: bitmap.width(),
+            height: bitmap.height(),
+            stride,
+            format,
+            pixels: std::slice::from_raw_parts(buffer, len).to_vec(),
+        })
+    }
+}
+
+/// Decode the thumbnail image embedded in a page, if it has one
+///
+/// Reading a page's `/Thumb` image is much cheaper than rendering the page,
+/// which makes it a good fast path for grid views. Few producers still write
+/// thumbnails, so be ready to fall back to `render_page` on `None`.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the thumbnail as an `ExtractedImage` at its native size, or `None`
+/// if the page has no embedded thumbnail or PDFium cannot decode it.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if PDFium cannot load the document or page.
+pub fn page_thumbnail(pdf_bytes: &[u8], page_index: usize) -> Result<Option<ExtractedImage>> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+
+    let bitmap = unsafe { Bitmap::from_handle(ffi::FPDFPage_GetThumbnailAsBitmap(page.handle())) };
+    Ok(bitmap.and_then(|bitmap| {
+        Some(ExtractedImage {
+            width: bitmap.width(),
+            height: bitmap.height(),
+            pixels: bitmap.to_bgra()?,
+        })
+    }))
+}
+
+/// Render one region of a page as it would appear in a larger render
+///
+/// The page is laid out at `full_width` x `full_height` pixels and only the
+/// `clip` rectangle of that layout is rasterized, so a corner of a page can be
+/// shown at high zoom without rendering the whole page at that size.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `full_width` - Width of the (virtual) full-page render in pixels
+/// * `full_height` - Height of the (virtual) full-page render in pixels
+/// * `clip` - `(x, y, w, h)` in pixels of the full-page render, origin top left
+///
+/// # Returns
+///
+/// Returns `w * h` tightly packed BGRA pixels, rendered over white with annotations.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty, a dimension is not
+/// positive, or `clip` is not inside the full-page render.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::RenderFailed` if the bitmap cannot be allocated or read.
+pub fn render_page_region(
+    pdf_bytes: &[u8],
+    page_index: usize,
+    full_width: u32,
+    full_height: u32,
+    clip: (i32, i32, i32, i32),
+) -> Result<Vec<u8>> {
+    let (x, y, w, h) = clip;
+    let (full_w, full_h) = match (i32::try_from(full_width), i32::try_from(full_height)) {
+        (Ok(fw), Ok(fh)) if fw > 0 && fh > 0 => (fw, fh),
+        _ => return Err(PdfiumError::InvalidData),
+    };
+    let inside = x >= 0
+        && y >= 0
+        && w > 0
+        && h > 0
+        && x.checked_add(w).is_some_and(|right| right <= full_w)
+        && y.checked_add(h).is_some_and(|bottom| bottom <= full_h);
+    if !inside {
+        return Err(PdfiumError::InvalidData);
+    }
+
+    let doc = Document::load(pdf_bytes)?;
+    let (width_pt, height_pt) = page_size_in(&doc, page_index)?;
+    if width_pt <= 0.0 || height_pt <= 0.0 {
+        return Err(PdfiumError::RenderFailed(format!(
+            "Page {} has an empty media box",
+            page_index
+        )));
+    }
+    let page = doc.page(page_index)?;
+    let options = RenderOptions::default();
+
+    unsafe {
+        let bitmap = Bitmap::from_handle(ffi::FPDFBitmap_Create(w, h, 1)).ok_or_else(|| {
+            PdfiumError::RenderFailed(format!("Failed to allocate {}x{} bitmap", w, h))
+        })?;
+        ffi::FPDFBitmap_FillRect(bitmap.handle, 0, 0, w, h, options.background as std::os::raw::c_ulong);
+
+        // PDFium first maps the page to a points-sized device; scale that up to
+        // the full render, then shift the clip's corner to the bitmap origin
+        let matrix = ffi::FS_MATRIX {
+            a: (full_w as f64 / width_pt) as f32,
+            b: 0.0,
+            c: 0.0,
+            d: (full_h as f64 / height_pt) as f32,
+            e: -x as f32,
+            f: -y as f32,
+        };
+        let clipping = ffi::FS_RECTF {
+            left: 0.0,
+            top: 0.0,
+            right: w as f32,
+            bottom: h as f32,
+        };
+        ffi::FPDF_RenderPageBitmapWithMatrix(
+            bitmap.handle,
+            page.handle(),
+            &matrix,
+            &clipping,
+            options.flags(),
+        );
+
+        bitmap
+            .to_bgra()
+            .ok_or_else(|| PdfiumError::RenderFailed("Unsupported bitmap format".to_string()))
+    }
+}
+
+/// How a page splits into square tiles at a given resolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TilePlan {
+    pub cols: u32,
+    pub rows: u32,
+    /// Width of the full-page render in pixels
+    pub full_width: u32,
+    /// Height of the full-page render in pixels
+    pub full_height: u32,
+}
+
+/// Work out the tile grid for rendering a page at `dpi`
+///
+/// The full-page size is rounded exactly as `render_page_at_dpi` rounds it, so
+/// tiles rendered with `render_page_region` at `full_width` x `full_height`
+/// line up with a whole-page render. Tiles in the last column and row are
+/// narrower or shorter when the size isn't a multiple of `tile_size`.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `dpi` - Render resolution in dots per inch
+/// * `tile_size` - Edge length of a tile in pixels
+///
+/// # Returns
+///
+/// Returns the column and row counts along with the full-page pixel size.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty, `dpi` is not
+/// positive, or `tile_size` is zero.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or page size cannot be read.
+pub fn tile_plan(pdf_bytes: &[u8], page_index: usize, dpi: f32, tile_size: u32) -> Result<TilePlan> {
+    // Written this way so NaN is rejected too
+    if !(dpi > 0.0 && dpi.is_finite()) || tile_size == 0 {
+        return Err(PdfiumError::InvalidData);
+    }
+
+    let (width_pt, height_pt) = page_size(pdf_bytes, page_index)?;
+    let full_width = points_to_pixels(width_pt, dpi);
+    let full_height = points_to_pixels(height_pt, dpi);
+
+    Ok(TilePlan {
+        cols: full_width.div_ceil(tile_size),
+        rows: full_height.div_ceil(tile_size),
+        full_width,
+        full_height,
+    })
+}
+
+/// Render a page and encode it as a baseline JPEG
+///
+/// Requires the `jpeg` feature. JPEG has no alpha channel, so the render is
+/// composited over white before encoding.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `width` - Output width in pixels (at most 65535)
+/// * `height` - Output height in pixels (at most 65535)
+/// * `quality` - JPEG quality, 1 (smallest) to 100 (best)
+///
+/// # Returns
+///
+/// Returns the encoded JPEG file.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty, `quality` is not in
+/// `1..=100`, or a dimension is zero or larger than JPEG allows.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::RenderFailed` if rendering or encoding fails.
+#[cfg(feature = "jpeg")]
+pub fn render_page_to_jpeg(
+    pdf_bytes: &[u8],
+    page_index: usize,
+    width: u32,
+    height: u32,
+    quality: u8,
+) -> Result<Vec<u8>> {
+    if !(1..=100).contains(&quality) {
+        return Err(PdfiumError::InvalidData);
+    }
+    let (w, h) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ => return Err(PdfiumError::InvalidData),
+    };
+
+    let bgra = render_page_with_options(pdf_bytes, page_index, width, height, RenderOptions::new())?;
+    encode_jpeg(&bgra, w, h, quality)
+}
+
+/// Encode tightly packed BGRA pixels as a JPEG, compositing over white
+#[cfg(feature = "jpeg")]
+fn encode_jpeg(bgra: &[u8], width: u16, height: u16, quality: u8) -> Result<Vec<u8>> {
+    let over_white = |c: u8, a: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+    let rgb: Vec<u8> = bgra
+        .chunks_exact(4)
+        .flat_map(|px| {
+            let a = px[3];
+            [over_white(px[2], a), over_white(px[1], a), over_white(px[0], a)]
+        })
+        .collect();
+
+    let mut jpeg = Vec::new();
+    jpeg_encoder::Encoder::new(&mut jpeg, quality)
+        .encode(&rgb, width, height, jpeg_encoder::ColorType::Rgb)
+        .map_err(|e| PdfiumError::RenderFailed(format!("JPEG encoding failed: {}", e)))?;
+    Ok(jpeg)
+}
+
+/// Encode tightly packed BGRA pixels as an 8-bit RGBA PNG
+#[cfg(feature = "zip")]
+fn encode_png(bgra: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
+    let mut rgba = bgra.to_vec();
+    bgra_to_rgba(&mut rgba);
+
+    let encode = || -> std::result::Result<Vec<u8>, png::EncodingError> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&rgba)?;
+        writer.finish()?;
+        Ok(out)
+    };
+    encode().map_err(|e| PdfiumError::RenderFailed(format!("PNG encoding failed: {}", e)))
+}
+
+/// Image encoding for `render_all_pages_to_zip`
+#[cfg(feature = "zip")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Lossless RGBA PNG (keeps transparency)
+    Png,
+    /// Baseline JPEG composited over white, `quality` from 1 to 100
+    Jpeg { quality: u8 },
+}
+
+/// Render every page and pack the images into a zip archive
+///
+/// Requires the `zip` feature. The document is loaded once for all pages.
+/// Entries are named `page-0001.png`, `page-0002.png`, ... (or `.jpg`), numbered
+/// from 1, and stored uncompressed since the images are already compressed.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `width` - Width of every image in pixels
+/// * `height` - Height of every image in pixels
+/// * `format` - Image encoding for the entries
+///
+/// # Returns
+///
+/// Returns the zip archive (empty of entries for a document without pages).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty, a dimension is zero,
+/// or a JPEG dimension or quality is out of range.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+/// Returns `PdfiumError::RenderFailed` if a page cannot be rendered or encoded,
+/// or the archive cannot be written.
+#[cfg(feature = "zip")]
+pub fn render_all_pages_to_zip(
+    pdf_bytes: &[u8],
+    width: u32,
+    height: u32,
+    format: ImageFormat,
+) -> Result<Vec<u8>> {
+    use std::io::Write;
+
+    // Validate up front rather than failing after rendering the first page
+    if let ImageFormat::Jpeg { quality } = format {
+        if !(1..=100).contains(&quality) || width > u16::MAX as u32 || height > u16::MAX as u32 {
+            return Err(PdfiumError::InvalidData);
+        }
+    }
+
+    let doc = Document::load(pdf_bytes)?;
+    let options = RenderOptions::new();
+    let zip_error = |e: &dyn std::fmt::Display| {
+        PdfiumError::RenderFailed(format!("Failed to write zip archive: {}", e))
+    };
+
+    let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let entry_options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    for page_index in 0..doc.page_count() {
+        let bgra = render_bitmap(&doc, page_index, width, height, &options)?
+            .to_bgra()
+            .ok_or_else(|| PdfiumError::RenderFailed("Unsupported bitmap format".to_string()))?;
+
+        let (name, image) = match format {
+            ImageFormat::Png => (
+                format!("page-{:04}.png", page_index + 1),
+                encode_png(&bgra, width, height)?,
+            ),
+            // Dimensions were checked against the JPEG limit above
+            ImageFormat::Jpeg { quality } => (
+                format!("page-{:04}.jpg", page_index + 1),
+                encode_jpeg(&bgra, width as u16, height as u16, quality)?,
+            ),
+        };
+
+        archive.start_file(name, entry_options).map_err(|e| zip_error(&e))?;
+        archive.write_all(&image).map_err(|e| zip_error(&e))?;
+    }
+
+    let cursor = archive.finish().map_err(|e| zip_error(&e))?;
+    Ok(cursor.into_inner())
+}
+
+/// Render a page as an SVG document
+///
+/// The bundled PDFium is built without an SVG device, so this currently
+/// always fails with `PdfiumError::Unsupported` once the page is validated.
+/// It exists so callers can probe for vector output and fall back to
+/// `render_page_with_options`.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::Unsupported` if SVG output is not compiled in.
+pub fn render_page_to_svg(pdf_bytes: &[u8], page_index: usize) -> Result<String> {
+    let doc = Document::load(pdf_bytes)?;
+    doc.check_page_index(page_index)?;
+    Err(PdfiumError::Unsupported("SVG rendering is not available in the linked PDFium"))
+}
+
+// ============================================================================
+// Embedded Images
+// ============================================================================
+
+/// An image decoded from a page (an image object, or the page's thumbnail)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedImage {
+    pub width: u32,
+    pub height: u32,
+    /// Tightly packed BGRA pixels (4 bytes per pixel, rows top to bottom)
+    pub pixels: Vec<u8>,
+}
+
+/// Extract the embedded images of a page as decoded bitmaps
+///
+/// Images are returned at their native pixel size, not as they appear on the page.
+/// Objects that aren't images, or whose bitmap PDFium can't decode, are skipped.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the page's images in content-stream order (empty if it has none).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or page cannot be loaded.
+pub fn extract_images(pdf_bytes: &[u8], page_index: usize) -> Result<Vec<ExtractedImage>> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+    let mut images = Vec::new();
+
+    unsafe {
+        let object_count = ffi::FPDFPage_CountObjects(page.handle());
+        for i in 0..object_count {
+            let object = ffi::FPDFPage_GetObject(page.handle(), i);
+            if object.is_null() || ffi::FPDFPageObj_GetType(object) != ffi::FPDF_PAGEOBJ_IMAGE {
+                continue;
+            }
+
+            let Some(bitmap) = Bitmap::from_handle(ffi::FPDFImageObj_GetBitmap(object)) else {
+                continue;
+            };
+            if let Some(pixels) = bitmap.to_bgra() {
+                images.push(ExtractedImage {
+                    width: bitmap.width(),
+                    height: bitmap.height(),
+                    pixels,
+                });
+            }
+        }
+    }
+
+    Ok(images)
+}
+
+/// Pixel size and effective resolution of an image as placed on a page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageRes {
+    pub pixel_width: u32,
+    pub pixel_height: u32,
+    /// Displayed width in points, measured along the image's own x axis
+    pub display_width_pt: f64,
+    /// Displayed height in points, measured along the image's own y axis
+    pub display_height_pt: f64,
+    pub dpi_x: f64,
+    pub dpi_y: f64,
+}
+
+/// Report the effective resolution of every image on a page
+///
+/// The resolution is the pixel size divided by the displayed size in inches,
+/// as computed by PDFium from the image's transform, so rotated and skewed
+/// placements are measured along the image axes rather than by their bounding
+/// box. Images that are scaled down to nothing report a DPI of 0 and a
+/// displayed size of 0.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns one `ImageRes` per image object, in content-stream order. Images
+/// inside form XObjects are not included, matching `extract_images`.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or page cannot be loaded.
+pub fn image_resolutions(pdf_bytes: &[u8], page_index: usize) -> Result<Vec<ImageRes>> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+    let mut resolutions = Vec::new();
+
+    unsafe {
+        let object_count = ffi::FPDFPage_CountObjects(page.handle());
+        for i in 0..object_count {
+            let object = ffi::FPDFPage_GetObject(page.handle(), i);
+            if object.is_null() || ffi::FPDFPageObj_GetType(object) != ffi::FPDF_PAGEOBJ_IMAGE {
+                continue;
+            }
+
+            let mut metadata = ffi::FPDF_IMAGEOBJ_METADATA::default();
+            if ffi::FPDFImageObj_GetImageMetadata(object, page.handle(), &mut metadata) == 0 {
+                continue;
+            }
+
+            // A degenerate transform can yield an infinite or NaN resolution
+            let finite_or_zero = |dpi: f32| if dpi.is_finite() { dpi as f64 } else { 0.0 };
+            let dpi_x = finite_or_zero(metadata.horizontal_dpi);
+            let dpi_y = finite_or_zero(metadata.vertical_dpi);
+            let displayed = |pixels: u32, dpi: f64| {
+                if dpi > 0.0 {
+                    pixels as f64 / dpi * 72.0
+                } else {
+                    0.0
+                }
+            };
+
+            resolutions.push(ImageRes {
+                pixel_width: metadata.width,
+                pixel_height: metadata.height,
+                display_width_pt: displayed(metadata.width, dpi_x),
+                display_height_pt: displayed(metadata.height, dpi_y),
+                dpi_x,
+                dpi_y,
+            });
+        }
+    }
+
+    Ok(resolutions)
+}
+
+// ============================================================================
+// Attachments
+// ============================================================================
+
+/// A file embedded in the document's `/EmbeddedFiles` name tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    /// The attachment's name as listed in the document (usually its file name)
+    pub name: String,
+    /// Size of the decoded file contents in bytes
+    pub size: usize,
+}
+
+/// Read an attachment's name, which PDFium returns as UTF-16LE
+unsafe fn attachment_name(attachment: ffi::FPDF_ATTACHMENT) -> String {
+    read_utf16_bytes(|buffer, length| ffi::FPDFAttachment_GetName(attachment, buffer, length))
+}
+
+/// Size of an attachment's decoded contents, or `None` if it has no readable file
+unsafe fn attachment_size(attachment: ffi::FPDF_ATTACHMENT) -> Option<usize> {
+    let mut size: std::os::raw::c_ulong = 0;
+    let ok = ffi::FPDFAttachment_GetFile(attachment, std::ptr::null_mut(), 0, &mut size);
+    (ok != 0).then_some(size as usize)
+}
+
+/// Iterate over a document's attachments as `(handle, name)`
+fn attachments<'d>(
+    doc: &'d Document,
+) -> impl Iterator<Item = (ffi::FPDF_ATTACHMENT, String)> + 'd {
+    let count = unsafe { ffi::FPDFDoc_GetAttachmentCount(doc.handle()) }.max(0);
+    (0..count).filter_map(move |index| unsafe {
+        let attachment = ffi::FPDFDoc_GetAttachment(doc.handle(), index);
+        (!attachment.is_null()).then(|| (attachment, attachment_name(attachment)))
+    })
+}
+
+/// List the files embedded in a PDF
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns one `Attachment` per embedded file, in name tree order. Entries
+/// without a readable file stream are skipped.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::PasswordRequired` if the document is encrypted.
+/// Returns `PdfiumError::ExtractionFailed` if PDFium cannot load the document.
+pub fn list_attachments(pdf_bytes: &[u8]) -> Result<Vec<Attachment>> {
+    let doc = Document::load(pdf_bytes)?;
+    Ok(attachments(&doc)
+        .filter_map(|(attachment, name)| {
+            let size = unsafe { attachment_size(attachment) }?;
+            Some(Attachment { name, size })
+        })
+        .collect())
+}
+
+/// Read the contents of an embedded file
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `name` - The attachment's name, as reported by `list_attachments`
+///
+/// # Returns
+///
+/// Returns the decoded file contents. If several attachments share the name,
+/// the first one is returned.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::PasswordRequired` if the document is encrypted.
+/// Returns `PdfiumError::ExtractionFailed` if PDFium cannot load the document
+/// or read the attachment's contents.
+/// Returns `PdfiumError::NotFound` if no attachment has that name.
+pub fn get_attachment(pdf_bytes: &[u8], name: &str) -> Result<Vec<u8>> {
+    let doc = Document::load(pdf_bytes)?;
+    let (attachment, _) = attachments(&doc)
+        .find(|(_, attachment_name)| attachment_name == name)
+        .ok_or_else(|| PdfiumError::NotFound(format!("attachment {:?}", name)))?;
+
+    unsafe {
+        let size = attachment_size(attachment).ok_or_else(|| {
+            PdfiumError::ExtractionFailed(format!("Attachment {:?} has no file data", name))
+        })?;
+
+        let mut contents: Vec<u8> = vec![0; size];
+        let mut written: std::os::raw::c_ulong = 0;
+        let ok = ffi::FPDFAttachment_GetFile(
+            attachment,
+            contents.as_mut_ptr() as *mut std::ffi::c_void,
+            size as std::os::raw::c_ulong,
+            &mut written,
+        );
+        if ok == 0 {
+            return Err(PdfiumError::ExtractionFailed(format!(
+                "Failed to read attachment {:?}",
+                name
+            )));
+        }
+        contents.truncate((written as usize).min(size));
+        Ok(contents)
+    }
+}
+
+// ============================================================================
+// Text Statistics
+// ============================================================================
+
+/// Character and word counts for one page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageStats {
+    pub page_index: usize,
+    /// Characters reported by `FPDFText_CountChars` (includes generated spaces/newlines)
+    pub char_count: usize,
+    /// Whitespace-separated tokens in the page text
+    pub word_count: usize,
+}
+
+/// Count characters and words on every page of a PDF
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns one `PageStats` per page. Pages that fail to load report zero counts.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded or a page
+/// reports an implausibly large character count.
+pub fn text_stats(pdf_bytes: &[u8]) -> Result<Vec<PageStats>> {
+    let doc = Document::load(pdf_bytes)?;
+
+    (0..doc.page_count())
+        .map(|page_index| {
+            let mut stats = PageStats {
+                page_index,
+                char_count: 0,
+                word_count: 0,
+            };
+
+            if let Some(page) = doc.try_page(page_index) {
+                if let Some(text_page) = page.text() {
+                    stats.char_count = text_page.char_count();
+                    stats.word_count = text_page.text()?.split_whitespace().count();
+                }
+            }
+
+            Ok(stats)
+        })
+        .collect()
+}
+
+/// Check whether a page has any extractable text
+///
+/// Cheaper than extracting the text: only the character count is read. Useful
+/// for routing image-only (scanned) pages to OCR.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns `true` if PDFium finds at least one character on the page.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or page cannot be loaded.
+pub fn page_has_text(pdf_bytes: &[u8], page_index: usize) -> Result<bool> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+    Ok(page.text().is_some_and(|text_page| text_page.char_count() > 0))
+}
+
+/// Detect the dominant language of each page's text
+///
+/// Requires the `whatlang` feature. Each page's extracted text is run through
+/// the `whatlang` detector on its own, so mixed-language documents get a
+/// language per page.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns one entry per page: the ISO 639-3 code of the detected language
+/// (e.g. `"eng"`, `"deu"`), or `None` when the page has no text, fails to
+/// load, or has too little text for a reliable detection.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be processed.
+#[cfg(feature = "whatlang")]
+pub fn detect_languages(pdf_bytes: &[u8]) -> Result<Vec<Option<String>>> {
+    let doc = Document::load(pdf_bytes)?;
+    (0..doc.page_count())
+        .map(|index| {
+            let Some(page) = doc.try_page(index) else {
+                return Ok(None);
+            };
+            let text = match page.text() {
+                Some(text_page) => text_page.text()?,
+                None => return Ok(None),
+            };
+
+            Ok(whatlang::detect(&text)
+                .filter(whatlang::Info::is_reliable)
+                .map(|info| info.lang().code().to_string()))
+        })
+        .collect()
+}
+
+/// Fraction of the page an image must cover to count as a scan in `is_scanned_document`
+const SCAN_IMAGE_MIN_COVERAGE: f64 = 0.5;
+
+/// Whether any image object on the page covers at least `min_area` square points
+fn page_has_image_covering(page: &Page, min_area: f64) -> bool {
+    unsafe {
+        (0..ffi::FPDFPage_CountObjects(page.handle())).any(|i| {
+            let object = ffi::FPDFPage_GetObject(page.handle(), i);
+            if object.is_null() || ffi::FPDFPageObj_GetType(object) != ffi::FPDF_PAGEOBJ_IMAGE {
+                return false;
+            }
+
+            let (mut left, mut bottom, mut right, mut top) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+            ffi::FPDFPageObj_GetBounds(object, &mut left, &mut bottom, &mut right, &mut top) != 0
+                && ((right - left) as f64 * (top - bottom) as f64).abs() >= min_area
+        })
+    }
+}
+
+/// Decide whether a document is a scan that needs OCR
+///
+/// A heuristic, in one pass over the document: it is considered scanned when
+/// every page carries an image covering at least half of the page and the
+/// pages together have fewer than `text_threshold` extractable characters
+/// (generated spaces and line breaks included). Scans that already have an
+/// OCR text layer therefore only count as scanned if the threshold is above
+/// their character count.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `text_threshold` - Character count at or above which the document has usable text
+///
+/// # Returns
+///
+/// Returns `true` if the document looks image-only. Documents without pages
+/// return `false`.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF or a page cannot be loaded.
+pub fn is_scanned_document(pdf_bytes: &[u8], text_threshold: usize) -> Result<bool> {
+    let doc = Document::load(pdf_bytes)?;
+    let page_count = doc.page_count();
+    if page_count == 0 {
+        return Ok(false);
+    }
+
+    let mut total_chars = 0;
+    for index in 0..page_count {
+        let (width, height) = page_size_in(&doc, index)?;
+        let page = doc.page(index)?;
+
+        total_chars += page.text().map_or(0, |text_page| text_page.char_count());
+        if total_chars >= text_threshold {
+            return Ok(false);
+        }
+        if !page_has_image_covering(&page, width * height * SCAN_IMAGE_MIN_COVERAGE) {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// A glyph with its bounding box in page points
+struct PlacedChar {
+    ch: char,
+    left: f64,
+    right: f64,
+    bottom: f64,
+    top: f64,
+    font_size: f64,
+}
+
+impl PlacedChar {
+    fn center_y(&self) -> f64 {
+        (self.bottom + self.top) / 2.0
+    }
+
+    fn height(&self) -> f64 {
+        self.top - self.bottom
+    }
+}
+
+/// Collect the positioned, non-whitespace characters of a text page
+fn placed_chars(text_page: &TextPage) -> Vec<PlacedChar> {
+    let count = i32::try_from(text_page.char_count()).unwrap_or(i32::MAX);
+    let mut chars = Vec::new();
+    let mut i = 0;
+    while i < count {
+        let (ch, advance) = text_page.char_at(i, count);
+        let (mut left, mut right, mut bottom, mut top) = (0.0, 0.0, 0.0, 0.0);
+        let placed = unsafe {
+            ffi::FPDFText_GetCharBox(
+                text_page.handle(),
+                i,
+                &mut left,
+                &mut right,
+                &mut bottom,
+                &mut top,
+            )
+        } != 0;
+
+        // Generated line breaks and whitespace carry no useful position; spacing
+        // is reconstructed from the gaps instead
+        if placed && !ch.is_whitespace() {
+            chars.push(PlacedChar {
+                ch,
+                left,
+                right,
+                bottom,
+                top,
+                font_size: unsafe { ffi::FPDFText_GetFontSize(text_page.handle(), i) },
+            });
+        }
+        i += advance;
+    }
+    chars
+}
+
+/// Group characters into lines, top to bottom, each sorted left to right
+///
+/// A character joins the current line when `same_line(first, c)` holds for
+/// the line's first (highest) character.
+fn cluster_lines(
+    mut chars: Vec<PlacedChar>,
+    same_line: impl Fn(&PlacedChar, &PlacedChar) -> bool,
+) -> Vec<Vec<PlacedChar>> {
+    chars.sort_by(|a, b| b.center_y().total_cmp(&a.center_y()));
+
+    let mut lines: Vec<Vec<PlacedChar>> = Vec::new();
+    for c in chars {
+        match lines.last_mut() {
+            Some(line) if same_line(&line[0], &c) => line.push(c),
+            _ => lines.push(vec![c]),
+        }
+    }
+
+    for line in &mut lines {
+        line.sort_by(|a, b| a.left.total_cmp(&b.left));
+    }
+    lines
+}
+
+/// Join a sorted line's characters, adding a space at gaps wider than a quarter line height
+fn line_text(line: &[PlacedChar]) -> String {
+    let height = line.iter().map(PlacedChar::height).fold(0.0, f64::max).max(1.0);
+    let mut text = String::new();
+    let mut previous_right: Option<f64> = None;
+    for c in line {
+        if previous_right.is_some_and(|right| c.left - right > height / 4.0) {
+            text.push(' ');
+        }
+        text.push(c.ch);
+        previous_right = Some(c.right);
+    }
+    text
+}
+
+/// Extract a page's text in visual order: top to bottom, then left to right
+///
+/// `extract_text` returns characters in content-stream order, which some
+/// producers scramble. This instead groups characters into lines by their
+/// vertical position (characters whose centers are within half a line
+/// height of each other share a line), sorts each line left to right, and
+/// inserts a space wherever the horizontal gap between two glyphs exceeds a
+/// quarter of the line height.
+///
+/// Columns are not detected: on a two-column page, lines at the same height
+/// in both columns are merged into one output line.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the page text, lines separated by `\n`.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF, page or text layer cannot be loaded.
+pub fn extract_text_ordered(pdf_bytes: &[u8], page_index: usize) -> Result<String> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+    let text_page = page.text().ok_or_else(|| {
+        PdfiumError::ExtractionFailed(format!("Failed to load text of page {}", page_index))
+    })?;
+
+    let lines = cluster_lines(placed_chars(&text_page), |first, c| {
+        (first.center_y() - c.center_y()).abs() <= first.height().max(c.height()).max(1.0) / 2.0
+    });
+
+    let lines: Vec<String> = lines.iter().map(|line| line_text(line)).collect();
+    Ok(lines.join("\n"))
+}
+
+/// A line of text in visual order, from `extract_lines`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    pub text: String,
+    /// `(left, bottom, right, top)` in page points, enclosing every glyph of the line
+    pub bbox: (f64, f64, f64, f64),
+}
+
+/// Split a page's text into visual lines with their bounding boxes
+///
+/// Characters are grouped into lines by vertical position and sorted left to
+/// right, as in `extract_text_ordered`. Here two characters share a line when
+/// their vertical centers are within half the page's median font size of each
+/// other, which keeps lines stable under small baseline shifts such as
+/// superscripts. Columns are not detected.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the lines top to bottom. Pages without positioned text return an
+/// empty vector.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF, page or text layer cannot be loaded.
+pub fn extract_lines(pdf_bytes: &[u8], page_index: usize) -> Result<Vec<TextLine>> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+    let text_page = page.text().ok_or_else(|| {
+        PdfiumError::ExtractionFailed(format!("Failed to load text of page {}", page_index))
+    })?;
+
+    let chars = placed_chars(&text_page);
+    let mut font_sizes: Vec<f64> = chars.iter().map(|c| c.font_size).collect();
+    font_sizes.sort_by(f64::total_cmp);
+    let median_font_size = font_sizes.get(font_sizes.len() / 2).copied().unwrap_or(0.0);
+    let tolerance = median_font_size.max(1.0) / 2.0;
+
+    let lines = cluster_lines(chars, |first, c| {
+        (first.center_y() - c.center_y()).abs() <= tolerance
+    });
+
+    Ok(lines
+        .iter()
+        .map(|line| {
+            let bbox = line.iter().fold(
+                (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+                |(left, bottom, right, top), c| {
+                    (left.min(c.left), bottom.min(c.bottom), right.max(c.right), top.max(c.top))
+                },
+            );
+            TextLine {
+                text: line_text(line),
+                bbox,
+            }
+        })
+        .collect())
+}
+
+/// Extract a page's text, leaving out invisible characters
+///
+/// Characters drawn with text render mode 3 (neither filled nor stroked) are
+/// skipped. That is how OCR tools lay their recognized text over a scanned
+/// image, so on scanned-then-OCR'd documents this drops the hidden layer. The
+/// spaces and line breaks PDFium generates between skipped characters are
+/// dropped along with them. `extract_text` is unaffected.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns the visible text of the page.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF, page or text layer cannot be loaded.
+pub fn extract_text_visible_only(pdf_bytes: &[u8], page_index: usize) -> Result<String> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+    let text_page = page.text().ok_or_else(|| {
+        PdfiumError::ExtractionFailed(format!("Failed to load text of page {}", page_index))
+    })?;
+
+    let count = i32::try_from(text_page.char_count()).unwrap_or(i32::MAX);
+    let mut text = String::new();
+    // Whether the last character backed by a text object was visible
+    let mut last_visible = true;
+    let mut i = 0;
+    while i < count {
+        let (ch, advance) = text_page.char_at(i, count);
+        let object = unsafe { ffi::FPDFText_GetTextObject(text_page.handle(), i) };
+        let generated = unsafe { ffi::FPDFText_IsGenerated(text_page.handle(), i) } == 1;
+
+        if generated || object.is_null() {
+            if last_visible {
+                text.push(ch);
+            }
+        } else {
+            let mode = unsafe { ffi::FPDFTextObj_GetTextRenderMode(object) };
+            last_visible = mode != ffi::FPDF_TEXTRENDERMODE_INVISIBLE;
+            if last_visible {
+                text.push(ch);
+            }
+        }
+        i += advance;
+    }
+
+    Ok(text)
+}
+
+/// Extract the text inside a rectangle of a page
+///
+/// Characters are included when PDFium considers them inside the rectangle,
+/// which is decided per character box, so a word straddling the edge may be
+/// cut.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `rect` - `(left, bottom, right, top)` in page points, origin at the bottom left
+///
+/// # Returns
+///
+/// Returns the text inside `rect` (empty if there is none).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty or `rect` is not a
+/// finite rectangle with `left <= right` and `bottom <= top`.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF, page or text layer cannot
+/// be loaded, or the region reports an implausibly large character count.
+pub fn extract_text_in_rect(
+    pdf_bytes: &[u8],
+    page_index: usize,
+    rect: (f64, f64, f64, f64),
+) -> Result<String> {
+    let (left, bottom, right, top) = rect;
+    let finite = [left, bottom, right, top].iter().all(|v| v.is_finite());
+    if !finite || left > right || bottom > top {
+        return Err(PdfiumError::InvalidData);
+    }
+
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+    let text_page = page.text().ok_or_else(|| {
+        PdfiumError::ExtractionFailed(format!("Failed to load text of page {}", page_index))
+    })?;
+
+    unsafe {
+        // With no buffer PDFium reports the length in UTF-16 units (no terminator)
+        let handle = text_page.handle();
+        let needed = ffi::FPDFText_GetBoundedText(
+            handle,
+            left,
+            top,
+            right,
+            bottom,
+            std::ptr::null_mut(),
+            0,
+        );
+        let length = usize::try_from(needed).unwrap_or(0);
+        if length == 0 {
+            return Ok(String::new());
+        }
+        if length > MAX_PAGE_TEXT_UNITS {
+            return Err(PdfiumError::ExtractionFailed(format!(
+                "Region reports {} characters, more than the {} supported",
+                length, MAX_PAGE_TEXT_UNITS
+            )));
+        }
+
+        let mut buffer: Vec<u16> = vec![0; length];
+        let written = ffi::FPDFText_GetBoundedText(
+            handle,
+            left,
+            top,
+            right,
+            bottom,
+            buffer.as_mut_ptr(),
+            needed,
+        );
+        buffer.truncate(usize::try_from(written).unwrap_or(0).min(length));
+
+        Ok(String::from_utf16_lossy(&buffer))
+    }
+}
+
+// ============================================================================
+// Text Styling
+// ============================================================================
+
+/// A character with the font it is drawn in
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledChar {
+    pub ch: char,
+    /// Font size in points
+    pub font_size: f64,
+    /// Font name as stored in the PDF (e.g. `ABCDEF+Helvetica-Bold`)
+    pub font_name: String,
+    /// PDF font descriptor flags (PDF 32000-1, table 123), e.g. bit 19 = force bold
+    pub flags: u32,
+}
+
+/// Read the font name and descriptor flags of one character
+unsafe fn char_font_info(text_page: &TextPage, index: i32) -> (String, u32) {
+    let mut flags: std::os::raw::c_int = 0;
+
+    // First call reports the required buffer size in bytes (UTF-8, including the NUL)
+    let needed =
+        ffi::FPDFText_GetFontInfo(text_page.handle(), index, std::ptr::null_mut(), 0, &mut flags);
+    if needed <= 1 {
+        return (String::new(), flags as u32);
+    }
+
+    let mut buffer: Vec<u8> = vec![0; needed as usize];
+    let written = ffi::FPDFText_GetFontInfo(
+        text_page.handle(),
+        index,
+        buffer.as_mut_ptr() as *mut std::ffi::c_void,
+        needed,
+        &mut flags,
+    );
+    buffer.truncate(written.min(needed) as usize);
+    if let Some(end) = buffer.iter().position(|&b| b == 0) {
+        buffer.truncate(end);
+    }
+
+    (String::from_utf8_lossy(&buffer).into_owned(), flags as u32)
+}
+
+/// Extract a page's characters together with their font size, name and flags
+///
+/// Characters outside the Basic Multilingual Plane arrive from PDFium as two
+/// UTF-16 units and are combined into one `StyledChar`, styled like its first half.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+///
+/// # Returns
+///
+/// Returns one entry per character in text order, including the spaces and
+/// line breaks PDFium generates (those carry no font and report size 0).
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF, page or text layer cannot be loaded.
+pub fn extract_text_with_style(pdf_bytes: &[u8], page_index: usize) -> Result<Vec<StyledChar>> {
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+    let text_page = page.text().ok_or_else(|| {
+        PdfiumError::ExtractionFailed(format!("Failed to load text of page {}", page_index))
+    })?;
+
+    let count = i32::try_from(text_page.char_count()).unwrap_or(i32::MAX);
+    let mut chars = Vec::with_capacity(count as usize);
+
+    unsafe {
+        let mut i = 0;
+        while i < count {
+            let (ch, advance) = text_page.char_at(i, count);
+            let (font_name, flags) = char_font_info(&text_page, i);
+            chars.push(StyledChar {
+                ch,
+                font_size: ffi::FPDFText_GetFontSize(text_page.handle(), i),
+                font_name,
+                flags,
+            });
+            i += advance;
+        }
+    }
+
+    Ok(chars)
+}
+
+/// A font referenced by text on at least one page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontInfo {
+    /// Base font name (e.g. `ABCDEF+Helvetica-Bold` for a subset)
+    pub name: String,
+    pub embedded: bool,
+    /// Zero-based pages using the font, ascending
+    pub used_on_pages: Vec<usize>,
+}
+
+/// Maximum form XObject nesting followed by `list_fonts`
+const MAX_FORM_DEPTH: usize = 16;
+
+/// Read a font's base name (empty if PDFium can't report one)
+unsafe fn font_base_name(font: ffi::FPDF_FONT) -> String {
+    // First call reports the required buffer size in bytes (including the NUL)
+    let needed = ffi::FPDFFont_GetBaseFontName(font, std::ptr::null_mut(), 0);
+    if needed <= 1 {
+        return String::new();
+    }
+
+    let mut buffer: Vec<u8> = vec![0; needed];
+    let written = ffi::FPDFFont_GetBaseFontName(
+        font,
+        buffer.as_mut_ptr() as *mut std::os::raw::c_char,
+        needed,
+    );
+    buffer.truncate(written.min(needed));
+    if let Some(end) = buffer.iter().position(|&b| b == 0) {
+        buffer.truncate(end);
+    }
+
+    String::from_utf8_lossy(&buffer).into_owned()
+}
+
+/// Record the fonts of `object`, descending into form XObjects
+unsafe fn collect_fonts(
+    object: ffi::FPDF_PAGEOBJECT,
+    page_index: usize,
+    depth: usize,
+    fonts: &mut Vec<FontInfo>,
+    by_name: &mut std::collections::HashMap<String, usize>,
+) {
+    match ffi::FPDFPageObj_GetType(object) {
+        ffi::FPDF_PAGEOBJ_TEXT => {
+            let font = ffi::FPDFTextObj_GetFont(object);
+            if font.is_null() {
+                return;
+            }
+            let name = font_base_name(font);
+            let embedded = ffi::FPDFFont_GetIsEmbedded(font) == 1;
+
+            let index = *by_name.entry(name.clone()).or_insert_with(|| {
+                fonts.push(FontInfo {
+                    name,
+                    embedded,
+                    used_on_pages: Vec::new(),
+                });
+                fonts.len() - 1
+            });
+            let info = &mut fonts[index];
+            info.embedded |= embedded;
+            if info.used_on_pages.last() != Some(&page_index) {
+                info.used_on_pages.push(page_index);
+            }
+        }
+        ffi::FPDF_PAGEOBJ_FORM if depth < MAX_FORM_DEPTH => {
+            let count = ffi::FPDFFormObj_CountObjects(object).max(0);
+            for i in 0..count as std::os::raw::c_ulong {
+                let child = ffi::FPDFFormObj_GetObject(object, i);
+                if !child.is_null() {
+                    collect_fonts(child, page_index, depth + 1, fonts, by_name);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// List the fonts used by text in a document
+///
+/// Fonts are de-duplicated by base name across pages; a name counts as
+/// embedded if any of its uses is. Text inside form XObjects is included.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns one `FontInfo` per distinct font name, in order of first use.
+/// Pages that fail to load are skipped.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+pub fn list_fonts(pdf_bytes: &[u8]) -> Result<Vec<FontInfo>> {
+    let doc = Document::load(pdf_bytes)?;
+    let mut fonts = Vec::new();
+    let mut by_name = std::collections::HashMap::new();
+
+    for page_index in 0..doc.page_count() {
+        let Some(page) = doc.try_page(page_index) else {
+            continue;
+        };
+
+        unsafe {
+            let object_count = ffi::FPDFPage_CountObjects(page.handle());
+            for i in 0..object_count {
+                let object = ffi::FPDFPage_GetObject(page.handle(), i);
+                if !object.is_null() {
+                    collect_fonts(object, page_index, 0, &mut fonts, &mut by_name);
+                }
+            }
+        }
+    }
+
+    Ok(fonts)
+}
+
+// ============================================================================
+// Document Security
+// ============================================================================
+
+/// Encryption state and permissions of a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecurityInfo {
+    pub is_encrypted: bool,
+    pub can_print: bool,
+    pub can_copy: bool,
+    pub can_modify: bool,
+}
+
+/// Probe a document's encryption and permissions
+///
+/// An encrypted document that can't be opened (no password or a wrong one)
+/// still reports `is_encrypted: true`, with every permission `false`.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `password` - Optional user or owner password
+///
+/// # Returns
+///
+/// Returns the document's `SecurityInfo`. Unencrypted documents allow everything.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded for other reasons.
+pub fn security_info(pdf_bytes: &[u8], password: Option<&str>) -> Result<SecurityInfo> {
+    let doc = match Document::load_with_password(pdf_bytes, password) {
+        Ok(doc) => doc,
+        Err(PdfiumError::PasswordRequired) => {
+            return Ok(SecurityInfo {
+                is_encrypted: true,
+                can_print: false,
+                can_copy: false,
+                can_modify: false,
+            });
+        }
+        Err(e) => return Err(e),
+    };
+
+    unsafe {
+        // Revision is -1 when the document has no security handler
+        let is_encrypted = ffi::FPDF_GetSecurityHandlerRevision(doc.handle()) >= 0;
+        let permissions = ffi::FPDF_GetDocPermissions(doc.handle());
+
+        Ok(SecurityInfo {
+            is_encrypted,
+            can_print: permissions & ffi::FPDF_PERM_PRINT != 0,
+            can_copy: permissions & ffi::FPDF_PERM_COPY != 0,
+            can_modify: permissions & ffi::FPDF_PERM_MODIFY != 0,
+        })
+    }
+}
+
+// ============================================================================
+// Digital Signatures
+// ============================================================================
+
+/// A signature field's metadata, from `signatures`
+///
+/// Nothing here is cryptographically verified: `contents` is the raw
+/// signature value (usually a DER-encoded PKCS#7 blob) and `byte_range` the
+/// `(offset, length)` pairs of the file it claims to cover, ready to be checked
+/// by a separate verifier.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignatureInfo {
+    /// The `/Reason` entry, if present and non-empty
+    pub reason: Option<String>,
+    /// The `/M` entry, verbatim in PDF date syntax
+    pub signing_time: Option<String>,
+    /// The `/ByteRange` array: flattened `(offset, length)` pairs
+    pub byte_range: Vec<i32>,
+    pub contents: Vec<u8>,
+}
+
+/// Read a signature's `/Reason`, which PDFium returns as UTF-16LE
+unsafe fn signature_reason(signature: ffi::FPDF_SIGNATURE) -> Option<String> {
+    let reason = read_utf16_bytes(|buffer, length| {
+        ffi::FPDFSignatureObj_GetReason(signature, buffer, length)
+    });
+    Some(reason).filter(|reason| !reason.is_empty())
+}
+
+/// Read a signature's `/M` time, which PDFium returns as NUL-terminated ASCII
+unsafe fn signature_time(signature: ffi::FPDF_SIGNATURE) -> Option<String> {
+    let needed = ffi::FPDFSignatureObj_GetTime(signature, std::ptr::null_mut(), 0);
+    if needed <= 1 {
+        return None;
+    }
+
+    let mut buffer: Vec<u8> = vec![0; needed as usize];
+    ffi::FPDFSignatureObj_GetTime(
+        signature,
+        buffer.as_mut_ptr() as *mut std::os::raw::c_char,
+        needed,
+    );
+    let end = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    buffer.truncate(end);
+
+    Some(String::from_utf8_lossy(&buffer).into_owned()).filter(|time| !time.is_empty())
+}
+
+/// Read a signature's `/ByteRange` array
+unsafe fn signature_byte_range(signature: ffi::FPDF_SIGNATURE) -> Vec<i32> {
+    let count = ffi::FPDFSignatureObj_GetByteRange(signature, std::ptr::null_mut(), 0);
+    let mut range: Vec<i32> = vec![0; count as usize];
+    if count > 0 {
+        let written = ffi::FPDFSignatureObj_GetByteRange(signature, range.as_mut_ptr(), count);
+        range.truncate(written.min(count) as usize);
+    }
+    range
+}
+
+/// Read a signature's raw `/Contents` bytes
+unsafe fn signature_contents(signature: ffi::FPDF_SIGNATURE) -> Vec<u8> {
+    let needed = ffi::FPDFSignatureObj_GetContents(signature, std::ptr::null_mut(), 0);
+    let mut contents: Vec<u8> = vec![0; needed as usize];
+    if needed > 0 {
+        let written = ffi::FPDFSignatureObj_GetContents(
+            signature,
+            contents.as_mut_ptr() as *mut std::ffi::c_void,
+            needed,
+        );
+        contents.truncate(written.min(needed) as usize);
+    }
+    contents
+}
+
+/// List the digital signatures in a PDF
+///
+/// Reports each signature's metadata and the byte ranges it covers; the
+/// signatures themselves are not verified.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns one `SignatureInfo` per signature, in document order. Unsigned
+/// documents return an empty vector.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::PasswordRequired` if the document is encrypted.
+/// Returns `PdfiumError::ExtractionFailed` if PDFium cannot load the document.
+pub fn signatures(pdf_bytes: &[u8]) -> Result<Vec<SignatureInfo>> {
+    let doc = Document::load(pdf_bytes)?;
+
+    unsafe {
+        let count = ffi::FPDF_GetSignatureCount(doc.handle()).max(0);
+        let mut signatures = Vec::with_capacity(count as usize);
+
+        for index in 0..count {
+            let signature = ffi::FPDF_GetSignatureObject(doc.handle(), index);
+            if signature.is_null() {
+                continue;
+            }
+
+            signatures.push(SignatureInfo {
+                reason: signature_reason(signature),
+                signing_time: signature_time(signature),
+                byte_range: signature_byte_range(signature),
+                contents: signature_contents(signature),
+            });
+        }
+
+        Ok(signatures)
+    }
+}
+
+// ============================================================================
+// Saving and Page Assembly
+// ============================================================================
+
+/// State shared with `write_block_trampoline` during a save
+struct SaveSink<'w, W: std::io::Write> {
+    writer: &'w mut W,
+    error: Option<std::io::Error>,
+}
+
+/// Write callback for `IPDF_StreamingIO_SaveWithCallback`; `user_data` is a `SaveSink<W>`
+unsafe extern "C" fn write_block_trampoline<W: std::io::Write>(
+    user_data: *mut std::os::raw::c_void,
+    data: *const std::os::raw::c_void,
+    size: std::os::raw::c_ulong,
+) -> std::os::raw::c_int {
+    let sink = &mut *(user_data as *mut SaveSink<W>);
+    if size == 0 {
+        return 1;
+    }
+    if data.is_null() || sink.error.is_some() {
+        return 0;
+    }
+
+    let chunk = std::slice::from_raw_parts(data as *const u8, size as usize);
+
+    // A panicking writer must not unwind into PDFium
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        sink.writer.write_all(chunk)
+    }));
+    match result {
+        Ok(Ok(())) => 1,
+        Ok(Err(e)) => {
+            sink.error = Some(e);
+            0
+        }
+        Err(_) => {
+            sink.error = Some(std::io::Error::other("writer panicked"));
+            0
+        }
+    }
+}
+
+/// Serialize a document through the streaming save helper
+fn save_document<W: std::io::Write>(
+    doc: &Document,
+    writer: &mut W,
+    flags: std::os::raw::c_int,
+) -> Result<()> {
+    let mut sink = SaveSink {
+        writer,
+        error: None,
+    };
+
+    let ok = unsafe {
+        ffi::IPDF_StreamingIO_SaveWithCallback(
+            doc.handle(),
+            Some(write_block_trampoline::<W>),
+            &mut sink as *mut SaveSink<W> as *mut std::os::raw::c_void,
+            flags,
+        )
+    };
+
+    if let Some(e) = sink.error {
+        return Err(PdfiumError::SaveFailed(e.to_string()));
+    }
+    if ok == 0 {
+        return Err(PdfiumError::SaveFailed(
+            "PDFium could not serialize the document".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Serialize a document into an owned buffer
+fn save_to_vec(doc: &Document) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    // Flags 0: full rewrite
+    save_document(doc, &mut bytes, 0)?;
+    Ok(bytes)
+}
+
+/// Concatenate several PDF documents into one
+///
+/// # Arguments
+///
+/// * `docs` - The input documents, in the order their pages should appear
+///
+/// # Returns
+///
+/// Returns the merged document as PDF bytes.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if `docs` is empty or any input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if an input cannot be loaded.
+/// Returns `PdfiumError::SaveFailed` if importing pages or serializing fails.
+pub fn merge_pdfs(docs: &[&[u8]]) -> Result<Vec<u8>> {
+    merge_pdfs_with_progress(docs, |_, _| {})
+}
+
+/// Concatenate several PDF documents into one, reporting progress
+///
+/// Same as `merge_pdfs`, but calls `progress(completed, total)` after each
+/// input document has been imported. The callback runs between PDFium calls,
+/// never inside one, so a panic in it unwinds through Rust code only and all
+/// open handles are released.
+pub fn merge_pdfs_with_progress(
+    docs: &[&[u8]],
+    mut progress: impl FnMut(usize, usize),
+) -> Result<Vec<u8>> {
+    if docs.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+
+    let merged = Document::new_empty()?;
+
+    for (i, pdf_bytes) in docs.iter().enumerate() {
+        let src = Document::load(pdf_bytes)?;
+
+        // Append after the pages imported so far (null range = all pages)
+        let insert_at = merged.page_count() as i32;
+        let ok = unsafe {
+            ffi::FPDF_ImportPages(merged.handle(), src.handle(), std::ptr::null(), insert_at)
+        };
+        if ok == 0 {
+            return Err(PdfiumError::SaveFailed(format!(
+                "Failed to import pages from document {}",
+                i
+            )));
+        }
+
+        drop(src);
+        progress(i + 1, docs.len());
+    }
+
+    save_to_vec(&merged)
+}
+
+/// Build a new PDF from selected pages of a document
+///
+/// Pages are copied in the order given; an index may appear more than once.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_indices` - Zero-based indices of the pages to copy
+///
+/// # Returns
+///
+/// Returns the new document as PDF bytes.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input or `page_indices` is empty.
+/// Returns `PdfiumError::PageOutOfRange` if any index does not exist.
+/// Returns `PdfiumError::SaveFailed` if importing pages or serializing fails.
+pub fn split_pdf(pdf_bytes: &[u8], page_indices: &[usize]) -> Result<Vec<u8>> {
+    split_pdf_with_progress(pdf_bytes, page_indices, |_, _| {})
+}
+
+/// Build a new PDF from selected pages of a document, reporting progress
+///
+/// Same as `split_pdf`, with `progress(completed, total)` counted in pages.
+/// All pages are imported in a single PDFium call so that shared resources
+/// (fonts, images) are copied once, which means progress is reported once,
+/// as `(total, total)`, when the import finishes. The callback never runs
+/// inside a PDFium call.
+pub fn split_pdf_with_progress(
+    pdf_bytes: &[u8],
+    page_indices: &[usize],
+    mut progress: impl FnMut(usize, usize),
+) -> Result<Vec<u8>> {
+    if page_indices.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+
+    let src = Document::load(pdf_bytes)?;
+    let indices = page_indices
+        .iter()
+        .map(|&index| src.check_page_index(index).map(|_| index as i32))
+        .collect::<Result<Vec<i32>>>()?;
+
+    let split = Document::new_empty()?;
+    let ok = unsafe {
+        ffi::FPDF_ImportPagesByIndex(
+            split.handle(),
+            src.handle(),
+            indices.as_ptr(),
+            indices.len() as std::os::raw::c_ulong,
+            0,
+        )
+    };
+    if ok == 0 {
+        return Err(PdfiumError::SaveFailed(
+            "Failed to import pages".to_string()
+        ));
+    }
+    progress(indices.len(), indices.len());
+
+    save_to_vec(&split)
+}
+
+/// Rotate pages and re-save the document
+///
+/// Rotations are relative to each page's current `/Rotate` value and turn the
+/// page clockwise, so `(0, 1)` turns page 0 by 90°. A page listed twice is
+/// rotated twice.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `rotations` - `(page_index, quarter_turns)` pairs, `quarter_turns` in `0..=3`
+///
+/// # Returns
+///
+/// Returns the rotated document as PDF bytes.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty or a quarter-turn count exceeds 3.
+/// Returns `PdfiumError::PageOutOfRange` if any page index does not exist.
+/// Returns `PdfiumError::SaveFailed` if serializing fails.
+pub fn rotate_pages(pdf_bytes: &[u8], rotations: &[(usize, u8)]) -> Result<Vec<u8>> {
+    if rotations.iter().any(|&(_, quarter_turns)| quarter_turns > 3) {
+        return Err(PdfiumError::InvalidData);
+    }
+
+    let doc = Document::load(pdf_bytes)?;
+
+    for &(page_index, quarter_turns) in rotations {
+        let page = doc.page(page_index)?;
+        unsafe {
+            let current = ffi::FPDFPage_GetRotation(page.handle());
+            ffi::FPDFPage_SetRotation(page.handle(), (current + quarter_turns as i32) % 4);
+        }
+    }
+
+    save_to_vec(&doc)
+}
+
+/// Remove pages from a PDF
+///
+/// Indices refer to the original document; duplicates are ignored. Pages are
+/// deleted from the highest index down so earlier deletions don't shift the
+/// pages still to be removed. Deleting every page yields a valid, empty PDF.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_indices` - Zero-based indices of the pages to remove, in any order
+///
+/// # Returns
+///
+/// Returns the remaining pages as a new PDF.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::PageOutOfRange` if an index does not exist; nothing is
+/// deleted in that case.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+/// Returns `PdfiumError::SaveFailed` if the result cannot be serialized.
+pub fn delete_pages(pdf_bytes: &[u8], page_indices: &[usize]) -> Result<Vec<u8>> {
+    let doc = Document::load(pdf_bytes)?;
+    for &page_index in page_indices {
+        doc.check_page_index(page_index)?;
+    }
+
+    let mut indices = page_indices.to_vec();
+    indices.sort_unstable_by(|a, b| b.cmp(a));
+    indices.dedup();
+
+    for page_index in indices {
+        // In range of a loaded document, so it fits in a c_int
+        unsafe { ffi::FPDFPage_Delete(doc.handle(), page_index as i32) };
+    }
+
+    save_to_vec(&doc)
+}
+
+/// Flatten form fields and annotations into page content
+///
+/// Annotation appearances are merged into each page's content stream as shown
+/// on screen, so the result renders the same in every viewer.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the flattened document as PDF bytes. Pages that fail to flatten are
+/// kept as they were; use `flatten_pdf_with_report` to find out which.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::ExtractionFailed` if the PDF cannot be loaded.
+/// Returns `PdfiumError::SaveFailed` if serializing fails.
+pub fn flatten_pdf(pdf_bytes: &[u8]) -> Result<Vec<u8>> {
+    flatten_pdf_with_report(pdf_bytes).map(|(bytes, _)| bytes)
+}
+
+/// Flatten form fields and annotations, reporting pages that could not be flattened
+///
+/// Same as `flatten_pdf`, but also returns the zero-based indices of pages that
+/// failed to load or flatten. Pages with nothing to flatten are not failures.
+pub fn flatten_pdf_with_report(pdf_bytes: &[u8]) -> Result<(Vec<u8>, Vec<usize>)> {
+    let doc = Document::load(pdf_bytes)?;
+    let mut failed_pages = Vec::new();
+
+    for page_index in 0..doc.page_count() {
+        let flattened = doc.try_page(page_index).is_some_and(|page| unsafe {
+            ffi::FPDFPage_Flatten(page.handle(), ffi::FLAT_NORMALDISPLAY) != ffi::FLATTEN_FAIL
+        });
+        if !flattened {
+            failed_pages.push(page_index);
+        }
+    }
+
+    Ok((save_to_vec(&doc)?, failed_pages))
+}
+
+/// Redact rectangular regions of a page and re-save the document
+///
+/// Text objects overlapping any region are deleted from the page (the whole
+/// object, even if it only partly overlaps), then each region is painted over
+/// with an opaque black rectangle. The text is really gone from the output,
+/// not just hidden. Text inside form XObjects and image content are not
+/// touched beyond the black box.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `page_index` - Zero-based page index
+/// * `rects` - Regions as `(left, bottom, right, top)` in page points
+///
+/// # Returns
+///
+/// Returns the redacted document as PDF bytes.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input or `rects` is empty, or a
+/// rectangle is not finite with `left < right` and `bottom < top`.
+/// Returns `PdfiumError::PageOutOfRange` if the page does not exist.
+/// Returns `PdfiumError::SaveFailed` if editing the page or serializing fails.
+pub fn redact_regions(
+    pdf_bytes: &[u8],
+    page_index: usize,
+    rects: &[(f64, f64, f64, f64)],
+) -> Result<Vec<u8>> {
+    let well_formed = |&(left, bottom, right, top): &(f64, f64, f64, f64)| {
+        [left, bottom, right, top].iter().all(|v| v.is_finite()) && left < right && bottom < top
+    };
+    if rects.is_empty() || !rects.iter().all(well_formed) {
+        return Err(PdfiumError::InvalidData);
+    }
+
+    let doc = Document::load(pdf_bytes)?;
+    let page = doc.page(page_index)?;
+
+    unsafe {
+        // Walk backwards so removals don't shift the indices still to visit
+        for i in (0..ffi::FPDFPage_CountObjects(page.handle())).rev() {
+            let object = ffi::FPDFPage_GetObject(page.handle(), i);
+            if object.is_null() || ffi::FPDFPageObj_GetType(object) != ffi::FPDF_PAGEOBJ_TEXT {
+                continue;
+            }
+
+            let (mut left, mut bottom, mut right, mut top) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+            if ffi::FPDFPageObj_GetBounds(object, &mut left, &mut bottom, &mut right, &mut top) == 0 {
+                continue;
+            }
+            let (left, bottom, right, top) = (left as f64, bottom as f64, right as f64, top as f64);
+            let hit = rects
+                .iter()
+                .any(|&(l, b, r, t)| left < r && right > l && bottom < t && top > b);
+
+            // Once removed, the object belongs to us and must be freed
+            if hit && ffi::FPDFPage_RemoveObject(page.handle(), object) != 0 {
+                ffi::FPDFPageObj_Destroy(object);
+            }
+        }
+
+        for &(left, bottom, right, top) in rects {
+            let rect = ffi::FPDFPageObj_CreateNewRect(
+                left as f32,
+                bottom as f32,
+                (right - left) as f32,
+                (top - bottom) as f32,
+            );
+            if rect.is_null() {
+                return Err(PdfiumError::SaveFailed(
+                    "Failed to create redaction rectangle".to_string()
+                ));
+            }
+            ffi::FPDFPageObj_SetFillColor(rect, 0, 0, 0, 255);
+            ffi::FPDFPath_SetDrawMode(rect, ffi::FPDF_FILLMODE_WINDING, 0);
+            ffi::FPDFPage_InsertObject(page.handle(), rect);
+        }
+
+        if ffi::FPDFPage_GenerateContent(page.handle()) == 0 {
+            return Err(PdfiumError::SaveFailed(format!(
+                "Failed to regenerate content of page {}",
+                page_index
+            )));
+        }
+    }
+
+    drop(page);
+    save_to_vec(&doc)
+}
+
+// ============================================================================
+// QPDF Document Operations
+// ============================================================================
+
+/// An error reported by the QPDF C API
+#[cfg(feature = "qpdf")]
+struct QpdfError {
+    code: std::os::raw::c_int,
+    message: String,
+}
+
+#[cfg(feature = "qpdf")]
+impl From<QpdfError> for PdfiumError {
+    fn from(e: QpdfError) -> Self {
+        if e.code == ffi::QPDF_E_PASSWORD {
+            PdfiumError::PasswordRequired
+        } else {
+            PdfiumError::ConversionFailed(e.message)
+        }
+    }
+}
+
+/// An owned QPDF object reading from a borrowed buffer
+///
+/// QPDF keeps pointing at the input buffer after `qpdf_read_memory`, so the
+/// bytes must outlive the handle, which the borrow enforces.
+#[cfg(feature = "qpdf")]
+struct Qpdf<'a> {
+    data: ffi::qpdf_data,
+    _input: PhantomData<&'a [u8]>,
+}
+
+#[cfg(feature = "qpdf")]
+impl<'a> Qpdf<'a> {
+    /// Parse a PDF from memory
+    fn read(pdf_bytes: &'a [u8], password: Option<&str>) -> std::result::Result<Self, QpdfError> {
+        let password = password.map(std::ffi::CString::new).transpose().map_err(|_| QpdfError {
+            code: 0,
+            message: "Password contains a NUL byte".to_string(),
+        })?;
+
+        let data = unsafe { ffi::qpdf_init() };
+        if data.is_null() {
+            return Err(QpdfError {
+                code: 0,
+                message: "Failed to create QPDF handle".to_string(),
+            });
+        }
+        let qpdf = Qpdf {
+            data,
+            _input: PhantomData,
+        };
+
+        unsafe {
+            // Warnings are still queued, just not printed to stderr
+            ffi::qpdf_set_suppress_warnings(qpdf.data, 1);
+
+            let rc = ffi::qpdf_read_memory(
+                qpdf.data,
+                c"input.pdf".as_ptr(),
+                pdf_bytes.as_ptr() as *const std::os::raw::c_char,
+                pdf_bytes.len() as u64,
+                password.as_ref().map_or(std::ptr::null(), |p| p.as_ptr()),
+            );
+            if rc & ffi::QPDF_ERRORS != 0 {
+                return Err(qpdf.take_error());
+            }
+        }
+
+        Ok(qpdf)
+    }
+
+    /// Pop the pending error from the handle
+    fn take_error(&self) -> QpdfError {
+        unsafe {
+            let error = ffi::qpdf_get_error(self.data);
+            if error.is_null() {
+                return QpdfError {
+                    code: 0,
+                    message: "Unknown QPDF error".to_string(),
+                };
+            }
+            self.describe(error)
+        }
+    }
+
+    /// Drain the queued warnings, oldest first
+    fn take_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        unsafe {
+            while ffi::qpdf_more_warnings(self.data) != 0 {
+                let warning = ffi::qpdf_next_warning(self.data);
+                if warning.is_null() {
+                    break;
+                }
+                warnings.push(self.describe(warning).message);
+            }
+        }
+        warnings
+    }
+
+    /// Read the code and message of an error or warning handle
+    unsafe fn describe(&self, error: ffi::qpdf_error) -> QpdfError {
+        let text = ffi::qpdf_get_error_full_text(self.data, error);
+        QpdfError {
+            code: ffi::qpdf_get_error_code(self.data, error),
+            message: if text.is_null() {
+                "Unknown QPDF error".to_string()
+            } else {
+                std::ffi::CStr::from_ptr(text).to_string_lossy().into_owned()
+            },
+        }
+    }
+
+    /// The decoded XMP packet of the catalog's `/Metadata` stream, if any
+    fn catalog_metadata(&self) -> Option<Vec<u8>> {
+        unsafe {
+            let root = ffi::qpdf_get_root(self.data);
+            let metadata = ffi::qpdf_oh_get_key(self.data, root, c"/Metadata".as_ptr());
+            self.with_stream_data(metadata, ffi::QPDF_DL_GENERALIZED, <[u8]>::to_vec)
+        }
+    }
+
+    /// Decode a stream and pass its data to `f`
+    ///
+    /// Returns `None` if `stream` isn't a stream, decoding fails, or the data is
+    /// still encoded with a filter beyond `decode_level`.
+    unsafe fn with_stream_data<T>(
+        &self,
+        stream: ffi::qpdf_oh,
+        decode_level: std::os::raw::c_int,
+        f: impl FnOnce(&[u8]) -> T,
+    ) -> Option<T> {
+        if ffi::qpdf_oh_is_stream(self.data, stream) == 0 {
+            return None;
+        }
+
+        let mut filtered: ffi::QPDF_BOOL = 0;
+        let mut buffer: *mut std::os::raw::c_uchar = std::ptr::null_mut();
+        let mut length: usize = 0;
+        let rc = ffi::qpdf_oh_get_stream_data(
+            self.data,
+            stream,
+            decode_level,
+            &mut filtered,
+            &mut buffer,
+            &mut length,
+        );
+        if rc & ffi::QPDF_ERRORS != 0 || buffer.is_null() {
+            // Leave the handle's error slot clear for later calls
+            let _ = self.take_error();
+            return None;
+        }
+
+        let result = f(std::slice::from_raw_parts(buffer, length));
+        ffi::free(buffer as *mut std::ffi::c_void);
+
+        (filtered != 0).then_some(result)
+    }
+
+    /// Write the document to memory
+    ///
+    /// `configure` runs after the writer is initialized and before writing, which is
+    /// where QPDF expects output options (linearization, encryption, ...) to be set.
+    fn write_to_vec(
+        &self,
+        configure: impl FnOnce(ffi::qpdf_data),
+    ) -> std::result::Result<Vec<u8>, QpdfError> {
+        unsafe {
+            if ffi::qpdf_init_write_memory(self.data) & ffi::QPDF_ERRORS != 0 {
+                return Err(self.take_error());
+            }
+
+            configure(self.data);
+
+            if ffi::qpdf_write(self.data) & ffi::QPDF_ERRORS != 0 {
+                return Err(self.take_error());
+            }
+
+            let length = ffi::qpdf_get_buffer_length(self.data);
+            let buffer = ffi::qpdf_get_buffer(self.data);
+            if buffer.is_null() {
+                return Ok(Vec::new());
+            }
+
+            Ok(std::slice::from_raw_parts(buffer, length).to_vec())
+        }
+    }
+}
+
+#[cfg(feature = "qpdf")]
+impl Drop for Qpdf<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::qpdf_cleanup(&mut self.data);
+        }
+    }
+}
+
+/// Outcome of `validate_pdf`
+#[cfg(feature = "qpdf")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// `true` only if QPDF parsed and checked the file without errors or warnings
+    pub is_valid: bool,
+    /// QPDF's warnings (recovered damage), followed by the fatal error if any
+    pub warnings: Vec<String>,
+}
+
+/// Check a PDF's structure with QPDF
+///
+/// Runs QPDF's full check (cross-reference table, object streams, page tree and
+/// content stream parsing). Files QPDF has to repair while reading still load
+/// in most viewers but are reported as invalid, with the repairs as warnings.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns a `ValidationReport`. Files QPDF cannot parse at all are reported as
+/// invalid rather than as an error.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::PasswordRequired` if the file is encrypted with a user password.
+#[cfg(feature = "qpdf")]
+pub fn validate_pdf(pdf_bytes: &[u8]) -> Result<ValidationReport> {
+    if pdf_bytes.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+    check_document_size(pdf_bytes.len())?;
+
+    let qpdf = match Qpdf::read(pdf_bytes, None) {
+        Ok(qpdf) => qpdf,
+        Err(e) if e.code == ffi::QPDF_E_PASSWORD => return Err(PdfiumError::PasswordRequired),
+        Err(e) => {
+            return Ok(ValidationReport {
+                is_valid: false,
+                warnings: vec![e.message],
+            })
+        }
+    };
+
+    let rc = unsafe { ffi::qpdf_check_pdf(qpdf.data) };
+    let mut warnings = qpdf.take_warnings();
+    let failed = rc & ffi::QPDF_ERRORS != 0;
+    if failed {
+        warnings.push(qpdf.take_error().message);
+    }
+
+    Ok(ValidationReport {
+        is_valid: !failed && warnings.is_empty(),
+        warnings,
+    })
+}
+
+/// Accessibility and archival claims of a document
+#[cfg(feature = "qpdf")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConformanceInfo {
+    /// The catalog marks the document as tagged (has a structure tree)
+    pub is_tagged: bool,
+    /// PDF/A level claimed in the XMP metadata, e.g. `PDF/A-2B`
+    pub pdfa_claim: Option<String>,
+}
+
+/// Find an XMP property written either as an attribute (`name="v"`) or an element (`<name>v</name>`)
+#[cfg(feature = "qpdf")]
+fn xmp_property(xmp: &str, name: &str) -> Option<String> {
+    for (start, _) in xmp.match_indices(name) {
+        let rest = xmp[start + name.len()..].trim_start();
+        let value = if let Some(rest) = rest.strip_prefix('=') {
+            let rest = rest.trim_start();
+            // An unquoted value is malformed; move on to the next occurrence
+            let quote = rest.chars().next().filter(|&c| c == '"' || c == '\'');
+            quote.and_then(|quote| rest[1..].split(quote).next())
+        } else if let Some(rest) = rest.strip_prefix('>') {
+            rest.split('<').next()
+        } else {
+            None
+        };
+
+        if let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Report whether a PDF is tagged and which PDF/A level it claims
+///
+/// The PDF/A claim is read from the `pdfaid:part` and `pdfaid:conformance`
+/// properties of the catalog's XMP metadata. It is only a claim: the file is
+/// not checked against the standard.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns a `ConformanceInfo`. `pdfa_claim` is `None` when there is no XMP
+/// metadata or it has no PDF/A identification.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::PasswordRequired` if the file is encrypted with a user password.
+/// Returns `PdfiumError::ExtractionFailed` if PDFium cannot load the PDF.
+/// Returns `PdfiumError::ConversionFailed` if QPDF cannot read the PDF.
+#[cfg(feature = "qpdf")]
+pub fn conformance_info(pdf_bytes: &[u8]) -> Result<ConformanceInfo> {
+    let doc = Document::load(pdf_bytes)?;
+    let is_tagged = unsafe { ffi::FPDFCatalog_IsTagged(doc.handle()) } != 0;
+
+    let qpdf = Qpdf::read(pdf_bytes, None)?;
+    let pdfa_claim = qpdf.catalog_metadata().and_then(|xmp| {
+        let xmp = String::from_utf8_lossy(&xmp);
+        let part = xmp_property(&xmp, "pdfaid:part")?;
+        Some(match xmp_property(&xmp, "pdfaid:conformance") {
+            Some(conformance) => format!("PDF/A-{}{}", part, conformance.to_uppercase()),
+            None => format!("PDF/A-{}", part),
+        })
+    });
+
+    Ok(ConformanceInfo {
+        is_tagged,
+        pdfa_claim,
+    })
+}
+
+/// Read the document's XMP metadata packet verbatim
+///
+/// Returns the decoded contents of the catalog's `/Metadata` stream exactly
+/// as stored, including custom namespaces and the `<?xpacket?>` wrapper, for
+/// callers that want more than the fields `document_metadata` understands.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the XML as a string, or `None` if the document has no XMP metadata
+/// or its stream uses a filter QPDF cannot decode.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::PasswordRequired` if the file is encrypted with a user password.
+/// Returns `PdfiumError::ConversionFailed` if QPDF cannot read the PDF or the
+/// packet is not UTF-8.
+#[cfg(feature = "qpdf")]
+pub fn xmp_metadata(pdf_bytes: &[u8]) -> Result<Option<String>> {
+    if pdf_bytes.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+    check_document_size(pdf_bytes.len())?;
+
+    let qpdf = Qpdf::read(pdf_bytes, None)?;
+    qpdf.catalog_metadata()
+        .map(|xmp| {
+            String::from_utf8(xmp).map_err(|_| {
+                PdfiumError::ConversionFailed("XMP packet is not valid UTF-8".to_string())
+            })
+        })
+        .transpose()
+}
+
+/// Linearize ("web-optimize") a PDF using QPDF
+///
+/// Linearized files put everything needed for the first page up front, so
+/// viewers fetching over HTTP ranges can display it before the download ends.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the linearized document as PDF bytes.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::ConversionFailed` with QPDF's message if the document
+/// cannot be read or linearized (including encrypted files without a key).
+#[cfg(feature = "qpdf")]
+pub fn linearize_pdf(pdf_bytes: &[u8]) -> Result<Vec<u8>> {
+    if pdf_bytes.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+    check_document_size(pdf_bytes.len())?;
+
+    let qpdf = Qpdf::read(pdf_bytes, None).map_err(|e| PdfiumError::ConversionFailed(e.message))?;
+    qpdf.write_to_vec(|data| unsafe { ffi::qpdf_set_linearization(data, 1) })
+        .map_err(|e| PdfiumError::ConversionFailed(e.message))
+}
+
+/// Shrink a PDF by compressing streams and packing objects into object streams
+///
+/// Equivalent to `qpdf --compress-streams=y --object-streams=generate`.
+/// Uncompressed streams are Flate-compressed and non-stream objects are
+/// grouped into compressed object streams, which requires PDF 1.5; QPDF
+/// raises the header version if needed. Streams that are already compressed
+/// are copied as they are.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns the rewritten document, or a copy of the input if rewriting did not
+/// make it smaller.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::ConversionFailed` with QPDF's message if the document
+/// cannot be read or written (including encrypted files without a key).
+#[cfg(feature = "qpdf")]
+pub fn compress_pdf(pdf_bytes: &[u8]) -> Result<Vec<u8>> {
+    if pdf_bytes.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+    check_document_size(pdf_bytes.len())?;
+
+    let qpdf = Qpdf::read(pdf_bytes, None).map_err(|e| PdfiumError::ConversionFailed(e.message))?;
+    let compressed = qpdf
+        .write_to_vec(|data| unsafe {
+            ffi::qpdf_set_compress_streams(data, 1);
+            ffi::qpdf_set_object_stream_mode(data, ffi::QPDF_O_GENERATE);
+        })
+        .map_err(|e| PdfiumError::ConversionFailed(e.message))?;
+
+    if compressed.len() < pdf_bytes.len() {
+        Ok(compressed)
+    } else {
+        Ok(pdf_bytes.to_vec())
+    }
+}
+
+/// Document information entries to write with `set_metadata`
+///
+/// Fields left `None` keep their current value.
+#[cfg(feature = "qpdf")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataUpdate {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+}
+
+/// Update the document information dictionary of a PDF using QPDF
+///
+/// Each `Some` field of `meta` replaces the matching `/Info` entry, creating
+/// the dictionary if the document has none; other entries are kept. Values are
+/// stored as PDF text strings, so any Unicode is allowed. An XMP packet in the
+/// catalog's `/Metadata`, if present, is left unchanged, so readers that
+/// prefer XMP may still show the old values.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `meta` - The entries to set
+///
+/// # Returns
+///
+/// Returns the rewritten document as PDF bytes.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty or a value
+/// contains a NUL character.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::ConversionFailed` with QPDF's message if the document
+/// cannot be read or written (including encrypted files without a key).
+#[cfg(feature = "qpdf")]
+pub fn set_metadata(pdf_bytes: &[u8], meta: MetadataUpdate) -> Result<Vec<u8>> {
+    if pdf_bytes.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+    check_document_size(pdf_bytes.len())?;
+
+    let fields = [
+        (c"/Title", meta.title),
+        (c"/Author", meta.author),
+        (c"/Subject", meta.subject),
+        (c"/Keywords", meta.keywords),
+        (c"/Creator", meta.creator),
+        (c"/Producer", meta.producer),
+    ];
+    let mut entries = Vec::new();
+    for (key, value) in fields {
+        if let Some(value) = value {
+            let value = std::ffi::CString::new(value).map_err(|_| PdfiumError::InvalidData)?;
+            entries.push((key, value));
+        }
+    }
+
+    let qpdf = Qpdf::read(pdf_bytes, None).map_err(|e| PdfiumError::ConversionFailed(e.message))?;
 
-        // Free the C string using QPDF's function
-        ffi::IPDF_QPDF_FreeString(json_ptr);
+    unsafe {
+        let trailer = ffi::qpdf_get_trailer(qpdf.data);
+        let mut info = ffi::qpdf_oh_get_key(qpdf.data, trailer, c"/Info".as_ptr());
+        if ffi::qpdf_oh_is_dictionary(qpdf.data, info) == 0 {
+            let dictionary = ffi::qpdf_oh_new_dictionary(qpdf.data);
+            info = ffi::qpdf_make_indirect_object(qpdf.data, dictionary);
+            ffi::qpdf_oh_replace_key(qpdf.data, trailer, c"/Info".as_ptr(), info);
+        }
 
-        Ok(json)
+        for (key, value) in &entries {
+            let string = ffi::qpdf_oh_new_unicode_string(qpdf.data, value.as_ptr());
+            ffi::qpdf_oh_replace_key(qpdf.data, info, key.as_ptr(), string);
+        }
     }
+
+    qpdf.write_to_vec(|_| {})
+        .map_err(|e| PdfiumError::ConversionFailed(e.message))
 }
 
-/// Convert a PDF document to JSON format using QPDF (C ABI for WASM)
-/// Returns pointer to null-terminated UTF-8 string, or null on error
-/// Caller must free the returned string with pdfium_wasm_free_string
-#[no_mangle]
-pub extern "C" fn pdfium_wasm_pdf_to_json(
-    pdf_data: *const u8,
-    pdf_len: usize,
-) -> *mut u8 {
-    if pdf_data.is_null() || pdf_len == 0 {
-        return std::ptr::null_mut();
+/// Outcome of `strip_javascript`
+#[cfg(feature = "qpdf")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StripResult {
+    /// The sanitized document
+    pub bytes: Vec<u8>,
+    /// Number of scripts removed (document-level entries plus JavaScript actions)
+    pub removed_count: usize,
+}
+
+/// How deep `/Next` action chains and `/Kids` trees are followed, so cycles terminate
+#[cfg(feature = "qpdf")]
+const MAX_OBJECT_DEPTH: usize = 32;
+
+/// Whether `oh` is an action dictionary with `/S /JavaScript`
+#[cfg(feature = "qpdf")]
+unsafe fn is_javascript_action(data: ffi::qpdf_data, oh: ffi::qpdf_oh) -> bool {
+    ffi::qpdf_oh_is_dictionary(data, oh) != 0
+        && ffi::qpdf_oh_is_name_and_equals(
+            data,
+            ffi::qpdf_oh_get_key(data, oh, c"/S".as_ptr()),
+            c"/JavaScript".as_ptr(),
+        ) != 0
+}
+
+/// Remove `owner[key]` if it is a JavaScript action, else strip its `/Next` actions
+///
+/// Returns the number of actions removed.
+#[cfg(feature = "qpdf")]
+unsafe fn strip_javascript_action(
+    data: ffi::qpdf_data,
+    owner: ffi::qpdf_oh,
+    key: &std::ffi::CStr,
+    depth: usize,
+) -> usize {
+    if depth > MAX_OBJECT_DEPTH || ffi::qpdf_oh_is_dictionary(data, owner) == 0 {
+        return 0;
     }
 
-    let pdf_bytes = unsafe { std::slice::from_raw_parts(pdf_data, pdf_len) };
+    let action = ffi::qpdf_oh_get_key(data, owner, key.as_ptr());
+    if is_javascript_action(data, action) {
+        ffi::qpdf_oh_remove_key(data, owner, key.as_ptr());
+        return 1;
+    }
+
+    // /Next is a single action or an array of them
+    let next = ffi::qpdf_oh_get_key(data, action, c"/Next".as_ptr());
+    if ffi::qpdf_oh_is_array(data, next) == 0 {
+        return strip_javascript_action(data, action, c"/Next", depth + 1);
+    }
 
-    match pdf_to_json(pdf_bytes) {
-        Ok(json) => {
-            let c_string = std::ffi::CString::new(json).unwrap_or_default();
-            c_string.into_raw() as *mut u8
+    let mut removed = 0;
+    for i in (0..ffi::qpdf_oh_get_array_n_items(data, next)).rev() {
+        let item = ffi::qpdf_oh_get_array_item(data, next, i);
+        if is_javascript_action(data, item) {
+            ffi::qpdf_oh_erase_item(data, next, i);
+            removed += 1;
+        } else {
+            removed += strip_javascript_action(data, item, c"/Next", depth + 1);
         }
-        Err(_) => std::ptr::null_mut(),
     }
+    removed
 }
 
-/// Cleanup PDFium library
+/// Strip the JavaScript actions from an object's `/AA` (additional actions) dictionary
+#[cfg(feature = "qpdf")]
+unsafe fn strip_javascript_additional_actions(data: ffi::qpdf_data, owner: ffi::qpdf_oh) -> usize {
+    let actions = ffi::qpdf_oh_get_key(data, owner, c"/AA".as_ptr());
+    if ffi::qpdf_oh_is_dictionary(data, actions) == 0 {
+        return 0;
+    }
+
+    // Collect the keys first: removing entries would invalidate the iterator
+    let mut keys = Vec::new();
+    ffi::qpdf_oh_begin_dict_key_iter(data, actions);
+    while ffi::qpdf_oh_dict_more_keys(data) != 0 {
+        let key = ffi::qpdf_oh_dict_next_key(data);
+        if !key.is_null() {
+            keys.push(std::ffi::CStr::from_ptr(key).to_owned());
+        }
+    }
+
+    keys.iter()
+        .map(|key| strip_javascript_action(data, actions, key, 0))
+        .sum()
+}
+
+/// Number of entries in a name tree
+#[cfg(feature = "qpdf")]
+unsafe fn name_tree_len(data: ffi::qpdf_data, node: ffi::qpdf_oh, depth: usize) -> usize {
+    if depth > MAX_OBJECT_DEPTH {
+        return 0;
+    }
+
+    // Leaf /Names arrays alternate keys and values
+    let names = ffi::qpdf_oh_get_key(data, node, c"/Names".as_ptr());
+    let mut len = if ffi::qpdf_oh_is_array(data, names) != 0 {
+        ffi::qpdf_oh_get_array_n_items(data, names).max(0) as usize / 2
+    } else {
+        0
+    };
+
+    let kids = ffi::qpdf_oh_get_key(data, node, c"/Kids".as_ptr());
+    if ffi::qpdf_oh_is_array(data, kids) != 0 {
+        for i in 0..ffi::qpdf_oh_get_array_n_items(data, kids) {
+            len += name_tree_len(data, ffi::qpdf_oh_get_array_item(data, kids, i), depth + 1);
+        }
+    }
+    len
+}
+
+/// Strip JavaScript from a form field and its descendants' `/AA` dictionaries
+#[cfg(feature = "qpdf")]
+unsafe fn strip_javascript_fields(
+    data: ffi::qpdf_data,
+    fields: ffi::qpdf_oh,
+    depth: usize,
+) -> usize {
+    if depth > MAX_OBJECT_DEPTH || ffi::qpdf_oh_is_array(data, fields) == 0 {
+        return 0;
+    }
+
+    let mut removed = 0;
+    for i in 0..ffi::qpdf_oh_get_array_n_items(data, fields) {
+        let field = ffi::qpdf_oh_get_array_item(data, fields, i);
+        removed += strip_javascript_additional_actions(data, field);
+        let kids = ffi::qpdf_oh_get_key(data, field, c"/Kids".as_ptr());
+        removed += strip_javascript_fields(data, kids, depth + 1);
+    }
+    removed
+}
+
+/// Remove embedded JavaScript from a PDF using QPDF
 ///
-/// This should be called at program exit. It's optional as the OS will clean up
-/// resources anyway, but it's good practice to call it explicitly.
-pub fn cleanup() {
+/// Removes the document-level `/JavaScript` name tree and every JavaScript
+/// action reachable from the catalog's `/OpenAction` and `/AA`, each page's
+/// `/AA`, each annotation's `/A` and `/AA`, and the `/AA` of every form field,
+/// including actions chained through `/Next`. Other action types are kept.
+/// Scripts inside XFA forms are not touched.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+///
+/// # Returns
+///
+/// Returns a `StripResult` with the rewritten document and the number of
+/// scripts removed. The document is rewritten even if nothing was removed.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::ConversionFailed` with QPDF's message if the document
+/// cannot be read or written (including encrypted files without a key).
+#[cfg(feature = "qpdf")]
+pub fn strip_javascript(pdf_bytes: &[u8]) -> Result<StripResult> {
+    if pdf_bytes.is_empty() {
+        return Err(PdfiumError::InvalidData);
+    }
+    check_document_size(pdf_bytes.len())?;
+
+    let qpdf = Qpdf::read(pdf_bytes, None).map_err(|e| PdfiumError::ConversionFailed(e.message))?;
+    let data = qpdf.data;
+    let mut removed_count = 0;
+
     unsafe {
-        ffi::FPDF_DestroyLibrary();
+        let root = ffi::qpdf_get_root(data);
+
+        let names = ffi::qpdf_oh_get_key(data, root, c"/Names".as_ptr());
+        if ffi::qpdf_oh_is_dictionary(data, names) != 0
+            && ffi::qpdf_oh_has_key(data, names, c"/JavaScript".as_ptr()) != 0
+        {
+            let scripts = ffi::qpdf_oh_get_key(data, names, c"/JavaScript".as_ptr());
+            removed_count += name_tree_len(data, scripts, 0);
+            ffi::qpdf_oh_remove_key(data, names, c"/JavaScript".as_ptr());
+        }
+
+        removed_count += strip_javascript_action(data, root, c"/OpenAction", 0);
+        removed_count += strip_javascript_additional_actions(data, root);
+
+        let page_count = ffi::qpdf_get_num_pages(data);
+        if page_count < 0 {
+            return Err(PdfiumError::ConversionFailed(qpdf.take_error().message));
+        }
+        for index in 0..page_count as usize {
+            let page = ffi::qpdf_get_page_n(data, index);
+            removed_count += strip_javascript_additional_actions(data, page);
+
+            let annotations = ffi::qpdf_oh_get_key(data, page, c"/Annots".as_ptr());
+            if ffi::qpdf_oh_is_array(data, annotations) != 0 {
+                for i in 0..ffi::qpdf_oh_get_array_n_items(data, annotations) {
+                    let annotation = ffi::qpdf_oh_get_array_item(data, annotations, i);
+                    removed_count += strip_javascript_action(data, annotation, c"/A", 0);
+                    removed_count += strip_javascript_additional_actions(data, annotation);
+                }
+            }
+        }
+
+        let form = ffi::qpdf_oh_get_key(data, root, c"/AcroForm".as_ptr());
+        let fields = ffi::qpdf_oh_get_key(data, form, c"/Fields".as_ptr());
+        removed_count += strip_javascript_fields(data, fields, 0);
     }
+
+    let bytes = qpdf
+        .write_to_vec(|_| {})
+        .map_err(|e| PdfiumError::ConversionFailed(e.message))?;
+    Ok(StripResult {
+        bytes,
+        removed_count,
+    })
 }
 
-/// Free a string returned by pdfium_wasm_extract_text or pdfium_wasm_pdf_to_json
-#[no_mangle]
-pub extern "C" fn pdfium_wasm_free_string(ptr: *mut u8) {
-    if !ptr.is_null() {
-        unsafe {
-            let _ = std::ffi::CString::from_raw(ptr as *mut i8);
-        }
+/// Remove encryption from a PDF using QPDF
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The encrypted PDF document as a byte slice
+/// * `password` - The user or owner password
+///
+/// # Returns
+///
+/// Returns an unencrypted copy of the document as PDF bytes. Unencrypted
+/// inputs are simply rewritten.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::PasswordRequired` if the password is wrong.
+/// Returns `PdfiumError::ConversionFailed` if QPDF cannot read or write the document.
+#[cfg(feature = "qpdf")]
+pub fn decrypt_pdf(pdf_bytes: &[u8], password: &str) -> Result<Vec<u8>> {
+    if pdf_bytes.is_empty() {
+        return Err(PdfiumError::InvalidData);
     }
+    check_document_size(pdf_bytes.len())?;
+
+    let qpdf = Qpdf::read(pdf_bytes, Some(password))?;
+    Ok(qpdf.write_to_vec(|data| unsafe { ffi::qpdf_set_preserve_encryption(data, 0) })?)
 }
 
-/// Cleanup PDFium library (C ABI for WASM)
-#[no_mangle]
-pub extern "C" fn pdfium_wasm_cleanup() {
-    cleanup();
+/// Encrypt a PDF with a user and owner password using QPDF
+///
+/// The output requires `user_password` to open; `owner_password` unlocks it
+/// with full rights. All permissions are granted to the user, as this only
+/// protects the document from being opened.
+///
+/// | `key_bits` | Scheme                   |
+/// |------------|--------------------------|
+/// | 40         | RC4, revision 2          |
+/// | 128        | AES-128, revision 4      |
+/// | 256        | AES-256, revision 6      |
+///
+/// 40-bit RC4 is trivially breakable and only offered for legacy readers.
+///
+/// # Arguments
+///
+/// * `pdf_bytes` - The PDF document as a byte slice
+/// * `user_password` - Password required to open the document
+/// * `owner_password` - Password granting full access
+/// * `key_bits` - Key length: 40, 128 or 256
+///
+/// # Returns
+///
+/// Returns the encrypted document as PDF bytes.
+///
+/// # Errors
+///
+/// Returns `PdfiumError::InvalidData` if the input is empty, `key_bits` is not
+/// 40, 128 or 256, or a password contains a NUL byte.
+/// Returns `PdfiumError::DocumentTooLarge` if `set_max_document_bytes` is exceeded.
+/// Returns `PdfiumError::PasswordRequired` if the input is already encrypted.
+/// Returns `PdfiumError::ConversionFailed` if QPDF cannot read or write the document.
+#[cfg(feature = "qpdf")]
+pub fn encrypt_pdf(
+    pdf_bytes: &[u8],
+    user_password: &str,
+    owner_password: &str,
+    key_bits: u16,
+) -> Result<Vec<u8>> {
+    if pdf_bytes.is_empty() || !matches!(key_bits, 40 | 128 | 256) {
+        return Err(PdfiumError::InvalidData);
+    }
+    check_document_size(pdf_bytes.len())?;
+
+    let user = std::ffi::CString::new(user_password).map_err(|_| PdfiumError::InvalidData)?;
+    let owner = std::ffi::CString::new(owner_password).map_err(|_| PdfiumError::InvalidData)?;
+
+    let qpdf = Qpdf::read(pdf_bytes, None)?;
+    Ok(qpdf.write_to_vec(|data| unsafe {
+        match key_bits {
+            40 => ffi::qpdf_set_r2_encryption_parameters_insecure(
+                data,
+                user.as_ptr(),
+                owner.as_ptr(),
+                1,
+                1,
+                1,
+                1,
+            ),
+            128 => ffi::qpdf_set_r4_encryption_parameters_insecure(
+                data,
+                user.as_ptr(),
+                owner.as_ptr(),
+                1,
+                1,
+                1,
+                1,
+                1,
+                1,
+                ffi::QPDF_R3P_FULL,
+                1,
+                1, // AES rather than RC4
+            ),
+            _ => ffi::qpdf_set_r6_encryption_parameters2(
+                data,
+                user.as_ptr(),
+                owner.as_ptr(),
+                1,
+                1,
+                1,
+                1,
+                1,
+                1,
+                ffi::QPDF_R3P_FULL,
+                1,
+            ),
+        }
+    })?)
 }
 
 // ============================================================================
@@ -410,7 +7667,9 @@ pub extern "C" fn pdfium_wasm_cleanup() {
 /// * `password` - Optional password (null or empty string for no password)
 ///
 /// # Returns
-/// * FPDF_DOCUMENT handle on success, null on failure
+/// * FPDF_DOCUMENT handle on success, null on failure. The handle counts as an
+///   open document, so `pdfium_wasm_cleanup` fails until it is closed with
+///   `pdfium_wasm_close_document`.
 ///
 /// # Safety
 /// The callback will be called multiple times by PDFium to read data.
@@ -422,11 +7681,45 @@ pub unsafe extern "C" fn pdfium_wasm_load_custom_document(
     user_data: *mut std::os::raw::c_void,
     password: *const std::os::raw::c_char,
 ) -> ffi::FPDF_DOCUMENT {
-    // Ensure PDFium is initialized
-    let _ = initialize();
+    ffi_guard(std::ptr::null_mut(), || {
+        // Hold the lifecycle lock until the handle is counted
+        let Ok(mut initialized) = lock_library() else {
+            return std::ptr::null_mut();
+        };
+        init_locked(&mut initialized);
+
+        // Call PDFium's streaming document loader
+        let document =
+            ffi::IPDF_StreamingIO_LoadDocument(file_size, get_block_callback, user_data, password);
+        if !document.is_null() {
+            // Keeps `cleanup()` from destroying the library under a live handle
+            OPEN_DOCUMENTS.fetch_add(1, Ordering::SeqCst);
+        }
+        document
+    })
+}
+
+/// Close a document returned by `pdfium_wasm_load_custom_document` (C ABI for WASM)
+///
+/// # Arguments
+/// * `document` - FPDF_DOCUMENT handle; null is ignored
+///
+/// # Safety
+/// `document` must be null or an open handle, and is invalid after this call.
+/// The loader's `user_data` may be released once this returns.
+#[no_mangle]
+pub unsafe extern "C" fn pdfium_wasm_close_document(document: ffi::FPDF_DOCUMENT) {
+    if document.is_null() {
+        return;
+    }
 
-    // Call PDFium's streaming document loader
-    ffi::IPDF_StreamingIO_LoadDocument(file_size, get_block_callback, user_data, password)
+    ffi_guard((), || {
+        ffi::FPDF_CloseDocument(document);
+        // A stray or repeated close must not wrap the count around
+        let _ = OPEN_DOCUMENTS.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |open| {
+            open.checked_sub(1)
+        });
+    })
 }
 
 /// Save PDF with custom writer callback (C ABI for WASM)
@@ -457,7 +7750,9 @@ pub unsafe extern "C" fn pdfium_wasm_save_as_copy_custom(
     }
 
     // Call PDFium's streaming save function
-    ffi::IPDF_StreamingIO_SaveWithCallback(document, write_block_callback, user_data, flags)
+    ffi_guard(0, || {
+        ffi::IPDF_StreamingIO_SaveWithCallback(document, write_block_callback, user_data, flags)
+    })
 }
 
 // ============================================================================
@@ -481,3 +7776,456 @@ pub unsafe extern "C" fn pdfium_wasm_save_as_copy_custom(
 // - IPDF_QPDF_StreamingFreeString
 // - IPDF_QPDF_StreamingFreeBuffer
 // ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assemble a PDF from numbered object bodies (object 1 must be the catalog)
+    fn build_pdf(objects: &[Vec<u8>]) -> Vec<u8> {
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, body) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            pdf.extend_from_slice(body);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = pdf.len();
+        let header = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        pdf.extend_from_slice(header.as_bytes());
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref
+            )
+            .as_bytes(),
+        );
+        pdf
+    }
+
+    fn stream_object(data: &[u8]) -> Vec<u8> {
+        let mut body = format!("<< /Length {} >>\nstream\n", data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        body
+    }
+
+    /// A one-page PDF showing `text` (raw string bytes) in Helvetica
+    ///
+    /// `to_unicode` is the body of an optional ToUnicode CMap for the font.
+    fn text_pdf(text: &[u8], to_unicode: Option<&str>) -> Vec<u8> {
+        let mut content = b"BT /F1 12 Tf 72 720 Td (".to_vec();
+        content.extend_from_slice(text);
+        content.extend_from_slice(b") Tj ET");
+
+        let font = match to_unicode {
+            Some(_) => "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /ToUnicode 6 0 R >>",
+            None => "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>",
+        };
+        let mut objects = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+              /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >>"
+                .to_vec(),
+            font.as_bytes().to_vec(),
+            stream_object(&content),
+        ];
+        if let Some(cmap) = to_unicode {
+            objects.push(stream_object(cmap.as_bytes()));
+        }
+        build_pdf(&objects)
+    }
+
+    #[test]
+    fn trim_text_buffer_drops_split_surrogate_pair() {
+        // "a", then the high half of U+1F600 cut off by the terminator
+        let mut buffer = vec![0x61, 0xD83D, 0];
+        trim_text_buffer(&mut buffer, 3);
+        assert_eq!(buffer, [0x61]);
+    }
+
+    #[test]
+    fn trim_text_buffer_keeps_whole_surrogate_pair() {
+        let mut buffer = vec![0x61, 0xD83D, 0xDE00, 0];
+        trim_text_buffer(&mut buffer, 4);
+        assert_eq!(buffer, [0x61, 0xD83D, 0xDE00]);
+    }
+
+    #[test]
+    fn trim_text_buffer_keeps_bmp_text() {
+        let mut buffer = vec![0x61, 0x4E2D, 0x62, 0, 0];
+        trim_text_buffer(&mut buffer, 4);
+        assert_eq!(buffer, [0x61, 0x4E2D, 0x62]);
+    }
+
+    #[test]
+    fn extract_text_decodes_supplementary_plane_text() {
+        // Map "A" to U+1F600 (an emoji) and "B" to U+2000B (a CJK Extension B ideograph)
+        let cmap = "/CIDInit /ProcSet findresource begin 12 dict begin begincmap\n\
+                    /CMapName /Adobe-Identity-UCS def /CMapType 2 def\n\
+                    1 begincodespacerange <00> <FF> endcodespacerange\n\
+                    2 beginbfchar <41> <D83DDE00> <42> <D840DC0B> endbfchar\n\
+                    endcmap CMapName currentdict /CMap defineresource pop end end";
+        let pdf = text_pdf(b"ABAB", Some(cmap));
+
+        let text = extract_text(&pdf).unwrap();
+        assert!(text.contains("\u{1F600}\u{2000B}\u{1F600}\u{2000B}"), "got {:?}", text);
+        assert!(!text.contains(char::REPLACEMENT_CHARACTER));
+    }
+
+    fn zero_page_pdf() -> Vec<u8> {
+        let doc = Document::new_empty().unwrap();
+        save_to_vec(&doc).unwrap()
+    }
+
+    /// Pages "A" and "B", plus a third page that `/Count` promises but that
+    /// does not exist, so PDFium reports three pages and fails to load the last
+    fn unloadable_page_pdf() -> Vec<u8> {
+        build_pdf(&[
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R 5 0 R] /Count 3 >>".to_vec(),
+            b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+              /Resources << /Font << /F1 7 0 R >> >> /Contents 4 0 R >>"
+                .to_vec(),
+            stream_object(b"BT /F1 12 Tf 72 720 Td (A) Tj ET"),
+            b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+              /Resources << /Font << /F1 7 0 R >> >> /Contents 6 0 R >>"
+                .to_vec(),
+            stream_object(b"BT /F1 12 Tf 72 720 Td (B) Tj ET"),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_vec(),
+        ])
+    }
+
+    #[test]
+    fn extract_text_with_offsets_matches_extract_text_with_unloadable_page() {
+        let pdf = unloadable_page_pdf();
+        let expected = extract_text(&pdf).unwrap();
+
+        let (text, offsets) = extract_text_with_offsets(&pdf).unwrap();
+        assert_eq!(text, expected);
+        assert_eq!(offsets.len(), 3);
+        assert!(text[offsets[1]..].starts_with('B'), "got {:?}", text);
+        assert_eq!(offsets[2], text.len());
+    }
+
+    #[test]
+    fn extract_text_report_matches_extract_text_with_unloadable_page() {
+        let pdf = unloadable_page_pdf();
+        let expected = extract_text(&pdf).unwrap();
+
+        let report = extract_text_report(&pdf).unwrap();
+        assert_eq!(report.text, expected);
+        assert_eq!(report.total_pages, 3);
+        assert_eq!(report.failed_pages, [2]);
+    }
+
+    #[test]
+    fn extract_text_capped_is_prefix_of_extract_text_with_unloadable_page() {
+        let pdf = unloadable_page_pdf();
+        let expected = extract_text(&pdf).unwrap();
+
+        assert_eq!(extract_text_capped(&pdf, usize::MAX).unwrap(), expected);
+        let capped = extract_text_capped(&pdf, 5).unwrap();
+        assert!(expected.starts_with(&capped), "got {:?}", capped);
+        assert_eq!(capped.chars().count(), 5);
+    }
+
+    #[test]
+    fn page_rotations_reports_unloadable_page_as_zero() {
+        let pdf = unloadable_page_pdf();
+        assert_eq!(page_rotations(&pdf).unwrap(), [0, 0, 0]);
+    }
+
+    #[test]
+    fn extract_text_on_zero_page_document_is_empty() {
+        let pdf = zero_page_pdf();
+        assert_eq!(extract_text(&pdf).unwrap(), "");
+    }
+
+    #[test]
+    fn extract_text_nonempty_rejects_zero_page_document() {
+        let pdf = zero_page_pdf();
+        assert_eq!(extract_text_nonempty(&pdf), Err(PdfiumError::NoPages));
+    }
+
+    #[test]
+    fn into_c_string_replaces_interior_nul() {
+        let ptr = into_c_string("a\0b".to_string());
+        let text = unsafe { std::ffi::CStr::from_ptr(ptr.cast()) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        pdfium_wasm_free_string(ptr);
+        assert_eq!(text, "a\u{FFFD}b");
+    }
+
+    #[cfg(feature = "qpdf")]
+    #[test]
+    fn is_empty_json_detects_blank_output() {
+        assert!(is_empty_json(b""));
+        assert!(is_empty_json(b"  "));
+        assert!(is_empty_json(b"{}"));
+        assert!(is_empty_json(b" { } "));
+        assert!(!is_empty_json(b"{\"version\":2}"));
+    }
+
+    #[cfg(feature = "jsonl")]
+    #[test]
+    fn extract_text_jsonl_escapes_page_text() {
+        // PDF literal string for: say "hi" \ ok
+        let pdf = text_pdf(br#"say "hi" \\ ok"#, None);
+
+        let jsonl = extract_text_jsonl(&pdf).unwrap();
+        assert!(jsonl.starts_with(r#"{"page":0,"text":""#), "got {:?}", jsonl);
+        assert!(jsonl.ends_with("}\n"));
+        let line: serde_json::Value = serde_json::from_str(jsonl.trim_end()).unwrap();
+        let text = line["text"].as_str().unwrap();
+        assert!(text.contains(r#"say "hi" \ ok"#), "got {:?}", text);
+    }
+
+    #[cfg(feature = "json-typed")]
+    #[test]
+    fn size_analysis_estimates_from_stream_dicts() {
+        let json = serde_json::json!({
+            "qpdf": [
+                { "jsonversion": 2 },
+                {
+                    "obj:1 0 R": { "value": { "/Type": "/Catalog" } },
+                    "obj:2 0 R": { "stream": { "dict": { "/Length": 10 } } },
+                    "obj:3 0 R": { "stream": { "dict": {
+                        "/Length": 20, "/Filter": "/FlateDecode", "/DL": 100
+                    } } },
+                    "obj:4 0 R": { "stream": { "dict": {
+                        "/Length": "5 0 R", "/Filter": "/DCTDecode", "/Subtype": "/Image",
+                        "/Width": 10, "/Height": 4, "/ColorSpace": "/DeviceRGB",
+                        "/BitsPerComponent": 8
+                    } } },
+                    "obj:5 0 R": { "value": 30 },
+                    "obj:6 0 R": { "stream": { "dict": {
+                        "/Length": 40, "/Filter": ["/FlateDecode"]
+                    } } }
+                }
+            ]
+        });
+
+        let analysis = size_analysis_of_json(&json, 1000).unwrap();
+        assert_eq!(
+            analysis,
+            SizeAnalysis {
+                file_size: 1000,
+                stream_count: 4,
+                compressed_stream_bytes: 10 + 20 + 30 + 40,
+                uncompressed_stream_bytes: 10 + 100 + 10 * 4 * 3 + 40,
+            }
+        );
+    }
+
+    /// `reset_for_testing`, retried while other tests still hold documents
+    #[cfg(feature = "test-utils")]
+    fn reset_when_idle() {
+        for _ in 0..100 {
+            match reset_for_testing() {
+                Err(PdfiumError::ResourcesStillOpen(_)) => {
+                    std::thread::sleep(std::time::Duration::from_millis(10))
+                }
+                result => return result.unwrap(),
+            }
+        }
+        panic!("documents stayed open");
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn reset_for_testing_allows_reinitialization() {
+        drop(SingleThreadPdfium::new().unwrap());
+
+        reset_when_idle();
+        assert_eq!(*PDFIUM_THREAD.lock().unwrap(), None);
+
+        initialize().unwrap();
+        assert!(is_initialized());
+        let text = extract_text(&text_pdf(b"Again", None)).unwrap();
+        assert!(text.contains("Again"), "got {:?}", text);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn reset_for_testing_refuses_while_documents_are_open() {
+        let pdf = text_pdf(b"Open", None);
+        let doc = Document::load(&pdf).unwrap();
+
+        assert!(matches!(
+            reset_for_testing(),
+            Err(PdfiumError::ResourcesStillOpen(open)) if open >= 1
+        ));
+        assert!(is_initialized());
+        assert_eq!(doc.page_count(), 1);
+    }
+
+    #[cfg(feature = "chrono")]
+    fn utc(ymd: (i32, u32, u32), hms: (u32, u32, u32)) -> chrono::DateTime<chrono::Utc> {
+        use chrono::TimeZone;
+        chrono::Utc
+            .with_ymd_and_hms(ymd.0, ymd.1, ymd.2, hms.0, hms.1, hms.2)
+            .unwrap()
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn parse_pdf_date_accepts_optional_prefix() {
+        let expected = Some(utc((2023, 1, 2), (3, 4, 5)));
+        assert_eq!(parse_pdf_date("D:20230102030405Z"), expected);
+        assert_eq!(parse_pdf_date("20230102030405Z"), expected);
+        assert_eq!(parse_pdf_date("D:20230102030405"), expected);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn parse_pdf_date_fills_in_truncated_fields() {
+        assert_eq!(parse_pdf_date("D:2023"), Some(utc((2023, 1, 1), (0, 0, 0))));
+        assert_eq!(parse_pdf_date("D:202306"), Some(utc((2023, 6, 1), (0, 0, 0))));
+        assert_eq!(parse_pdf_date("D:2023061214"), Some(utc((2023, 6, 12), (14, 0, 0))));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn parse_pdf_date_applies_offsets() {
+        assert_eq!(
+            parse_pdf_date("D:20230102030405+05'30'"),
+            Some(utc((2023, 1, 1), (21, 34, 5)))
+        );
+        assert_eq!(
+            parse_pdf_date("D:20230102030405-08'00"),
+            Some(utc((2023, 1, 2), (11, 4, 5)))
+        );
+        assert_eq!(
+            parse_pdf_date("D:20230102030405Z00'00'"),
+            Some(utc((2023, 1, 2), (3, 4, 5)))
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn parse_pdf_date_rejects_malformed_dates() {
+        // Odd digit counts
+        assert_eq!(parse_pdf_date("D:20231"), None);
+        assert_eq!(parse_pdf_date("D:2023010"), None);
+        // Out-of-range fields
+        assert_eq!(parse_pdf_date("D:20231302"), None);
+        assert_eq!(parse_pdf_date("D:2023010224"), None);
+        assert_eq!(parse_pdf_date("D:20230102030405+24'00'"), None);
+        // Trailing garbage
+        assert_eq!(parse_pdf_date("D:2023x"), None);
+        assert_eq!(parse_pdf_date("D:20230102030405Zjunk"), None);
+        assert_eq!(parse_pdf_date("D:20230102030405+05'30'x"), None);
+        assert_eq!(parse_pdf_date("D:20230102030405Z05'00'"), None);
+    }
+
+    #[test]
+    fn normalize_text_folds_line_endings() {
+        assert_eq!(normalize_text("a\r\nb\rc\nd"), "a\nb\nc\nd");
+    }
+
+    #[test]
+    fn normalize_text_collapses_spaces_and_tabs() {
+        assert_eq!(normalize_text("  a \t  b\t\tc  \n\td"), "a b c\nd");
+    }
+
+    #[test]
+    fn normalize_text_joins_hyphenated_words() {
+        assert_eq!(normalize_text("an exam-\nple here"), "an example here");
+        assert_eq!(normalize_text("an exam- \r\n  ple"), "an example");
+    }
+
+    #[test]
+    fn normalize_text_keeps_other_hyphens() {
+        assert_eq!(normalize_text("exam-\nPle"), "exam-\nPle");
+        assert_eq!(normalize_text("2-\nx"), "2-\nx");
+        assert_eq!(normalize_text("exam-\n\nple"), "exam-\n\nple");
+    }
+
+    #[test]
+    fn normalize_text_keeps_blank_lines_and_page_separators() {
+        assert_eq!(normalize_text("a\n\n\nb"), "a\n\n\nb");
+        let text = format!("one{}two", PAGE_SEPARATOR);
+        assert_eq!(normalize_text(&text), text);
+        let text = format!("hyphen-{}lower", PAGE_SEPARATOR);
+        assert_eq!(normalize_text(&text), text);
+    }
+
+    #[test]
+    fn page_count_scan_matches_page_objects() {
+        assert_eq!(page_count_scan(b"<< /Type /Page /Parent 2 0 R >>"), 1);
+        assert_eq!(page_count_scan(b"<</Type/Page/Parent 2 0 R>>"), 1);
+        assert_eq!(page_count_scan(b"<< /Type\r\n/Page >>"), 1);
+        assert_eq!(page_count_scan(b"<< /Type /Page >> << /Type /Page >>"), 2);
+    }
+
+    #[test]
+    fn page_count_scan_skips_other_types() {
+        assert_eq!(page_count_scan(b"<< /Type /Pages /Kids [] /Count 0 >>"), 0);
+        assert_eq!(page_count_scan(b"<< /Type /PageLabel /S /D >>"), 0);
+        assert_eq!(page_count_scan(b"<< /Type /Catalog >>"), 0);
+        assert_eq!(page_count_scan(b"<< /Type"), 0);
+    }
+
+    #[test]
+    fn page_count_scan_matches_at_end_of_input() {
+        assert_eq!(page_count_scan(b"<< /Type /Page"), 1);
+        assert_eq!(page_count_scan(b"/Type/Page"), 1);
+    }
+
+    #[test]
+    fn page_count_scan_counts_fixture_pages() {
+        assert_eq!(page_count_scan(&text_pdf(b"Hello", None)), 1);
+    }
+
+    #[cfg(feature = "qpdf")]
+    #[test]
+    fn xmp_property_reads_attributes_and_elements() {
+        assert_eq!(
+            xmp_property(r#"<rdf:Description pdfaid:part="2"/>"#, "pdfaid:part").as_deref(),
+            Some("2")
+        );
+        assert_eq!(
+            xmp_property("<pdfaid:part>2</pdfaid:part>", "pdfaid:part").as_deref(),
+            Some("2")
+        );
+        assert_eq!(
+            xmp_property("<rdf:Description pdfaid:conformance = 'B'/>", "pdfaid:conformance")
+                .as_deref(),
+            Some("B")
+        );
+    }
+
+    #[cfg(feature = "qpdf")]
+    #[test]
+    fn xmp_property_skips_empty_values() {
+        let xmp = r#"<rdf:Description pdfaid:part=""/><pdfaid:part>3</pdfaid:part>"#;
+        assert_eq!(xmp_property(xmp, "pdfaid:part").as_deref(), Some("3"));
+        let xmp = "<rdf:Description pdfaid:part=2/><pdfaid:part> 1 </pdfaid:part>";
+        assert_eq!(xmp_property(xmp, "pdfaid:part").as_deref(), Some("1"));
+        assert_eq!(xmp_property("<pdfaid:part></pdfaid:part>", "pdfaid:part"), None);
+        assert_eq!(xmp_property("<x:xmpmeta/>", "pdfaid:part"), None);
+    }
+
+    #[test]
+    fn bgra_to_rgba_swaps_blue_and_red() {
+        let mut buf = [1, 2, 3, 4, 5, 6, 7, 8];
+        bgra_to_rgba(&mut buf);
+        assert_eq!(buf, [3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of 4")]
+    fn bgra_to_rgba_rejects_partial_pixel() {
+        let mut buf = [1, 2, 3, 4, 5, 6];
+        bgra_to_rgba(&mut buf);
+    }
+}